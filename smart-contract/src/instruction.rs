@@ -0,0 +1,768 @@
+// Client-side builders, one per BullPosterInstruction variant.
+//
+// Each builder returns the accounts in the order the handler reads them, with the
+// signer and writable flags it needs. Accounts at fixed seeds (the config, program
+// state, token mint, leaderboard and claim vault) are derived here; everything
+// else is passed in by the caller, who can derive it with the `pda` module.
+// Pausable instructions whose handlers do not otherwise read the config get it
+// appended last, since the pause check looks for it anywhere in the account list.
+
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+use crate::{legacy, pda, BullPosterInstruction, GlobalConfig};
+
+fn build(
+    program_id: &Pubkey,
+    instruction: BullPosterInstruction,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    Instruction::new_with_bytes(*program_id, &instruction.pack(), accounts)
+}
+
+pub fn initialize_program(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    initial_authority: Option<Pubkey>,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::InitializeProgram { initial_authority },
+        vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(pda::token_mint_address(program_id).0, false),
+            AccountMeta::new(pda::program_state_address(program_id).0, false),
+            AccountMeta::new(pda::leaderboard_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+// For deployments initialized before GlobalConfig existed
+pub fn initialize_config(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    initial_authority: Option<Pubkey>,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::InitializeConfig { initial_authority },
+        vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(pda::config_address(program_id).0, false),
+            AccountMeta::new_readonly(pda::token_mint_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_raid_program(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    user_token_account: &Pubkey,
+    raid_program: &Pubkey,
+    raid_program_token_account: &Pubkey,
+    raid_programs_page: &Pubkey,
+    user_card: &Pubkey,
+    name: String,
+    description: String,
+    profile_picture_url: String,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::CreateRaidProgram {
+            name,
+            description,
+            profile_picture_url,
+        },
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new(*raid_program, false),
+            AccountMeta::new(*raid_program_token_account, false),
+            AccountMeta::new(pda::program_state_address(program_id).0, false),
+            AccountMeta::new(*raid_programs_page, false),
+            AccountMeta::new(*user_card, false),
+            AccountMeta::new_readonly(pda::token_mint_address(program_id).0, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn authority_mint(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    recipient_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::AuthorityMint { amount },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pda::token_mint_address(program_id).0, false),
+            AccountMeta::new(*recipient_token_account, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+// Accounts shared by create_raid and create_pvp_raid
+#[allow(clippy::too_many_arguments)]
+fn create_raid_accounts(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    current_competition: &Pubkey,
+    new_competition: &Pubkey,
+    raid_program: &Pubkey,
+    current_raid_card: &Pubkey,
+    new_raid_card: &Pubkey,
+    raids_page: &Pubkey,
+    competitions_page: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(*current_competition, false),
+        AccountMeta::new(*new_competition, false),
+        AccountMeta::new(*raid_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(*current_raid_card, false),
+        AccountMeta::new(*new_raid_card, false),
+        AccountMeta::new(pda::program_state_address(program_id).0, false),
+        AccountMeta::new(*raids_page, false),
+        AccountMeta::new(*competitions_page, false),
+        AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+    ]
+}
+
+// Join the current competition of a non-PvP type, or open the next one. PvP raids
+// need the challenged program and are built with create_pvp_raid.
+#[allow(clippy::too_many_arguments)]
+pub fn create_raid(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    current_competition: &Pubkey,
+    new_competition: &Pubkey,
+    raid_program: &Pubkey,
+    current_raid_card: &Pubkey,
+    new_raid_card: &Pubkey,
+    raids_page: &Pubkey,
+    competitions_page: &Pubkey,
+    competition_type: String,
+) -> Instruction {
+    assert_ne!(
+        competition_type, "PvP",
+        "PvP raids are built with create_pvp_raid"
+    );
+
+    build(
+        program_id,
+        BullPosterInstruction::CreateRaid { competition_type },
+        create_raid_accounts(
+            program_id,
+            user,
+            current_competition,
+            new_competition,
+            raid_program,
+            current_raid_card,
+            new_raid_card,
+            raids_page,
+            competitions_page,
+        ),
+    )
+}
+
+// Challenge `challenged_program` to a PvP raid
+#[allow(clippy::too_many_arguments)]
+pub fn create_pvp_raid(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    current_competition: &Pubkey,
+    new_competition: &Pubkey,
+    raid_program: &Pubkey,
+    current_raid_card: &Pubkey,
+    new_raid_card: &Pubkey,
+    raids_page: &Pubkey,
+    competitions_page: &Pubkey,
+    challenged_program: &Pubkey,
+) -> Instruction {
+    let mut accounts = create_raid_accounts(
+        program_id,
+        user,
+        current_competition,
+        new_competition,
+        raid_program,
+        current_raid_card,
+        new_raid_card,
+        raids_page,
+        competitions_page,
+    );
+    accounts.push(AccountMeta::new_readonly(*challenged_program, false));
+
+    build(
+        program_id,
+        BullPosterInstruction::CreateRaid {
+            competition_type: "PvP".to_string(),
+        },
+        accounts,
+    )
+}
+
+pub fn accept_pvp_request(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    competition: &Pubkey,
+    challenged_program: &Pubkey,
+    raid_card: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::AcceptPVPRequest,
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(*competition, false),
+            AccountMeta::new(*challenged_program, false),
+            AccountMeta::new(*raid_card, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn burn_tokens(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    user_card: &Pubkey,
+    user_token_account: &Pubkey,
+    raid_program: &Pubkey,
+    competition: &Pubkey,
+    raid_card: &Pubkey,
+    burn_amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::BurnTokens { burn_amount },
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new_readonly(*user_card, false),
+            AccountMeta::new(
+                pda::burn_ledger_address(program_id, user, raid_card).0,
+                false,
+            ),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new(pda::token_mint_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*raid_program, false),
+            AccountMeta::new_readonly(*competition, false),
+            AccountMeta::new(*raid_card, false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn enroll_in_program(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    user_card: &Pubkey,
+    raid_program: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::EnrollInProgram,
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(*user_card, false),
+            AccountMeta::new_readonly(*raid_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn create_user_card(program_id: &Pubkey, user: &Pubkey, user_card: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::CreateUserCard,
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(*user_card, false),
+            AccountMeta::new(pda::program_state_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn update_user_card(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    user_card: &Pubkey,
+    user_email: String,
+    user_twitter_handle: String,
+    user_dob: String,
+    profile_picture_url: String,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::UpdateUserCard {
+            user_email,
+            user_twitter_handle,
+            user_dob,
+            profile_picture_url,
+        },
+        vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(*user_card, false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+// `raids` holds a (raid card, raid program) pair per enrolled program, in enrollment order
+pub fn finalize_competition(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    competition: &Pubkey,
+    raids: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*competition, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        AccountMeta::new_readonly(pda::token_mint_address(program_id).0, false),
+        AccountMeta::new_readonly(spl_token_2022::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new(pda::reward_vault_address(program_id, competition).0, false),
+    ];
+    for (raid_card, raid_program) in raids {
+        accounts.push(AccountMeta::new(*raid_card, false));
+        accounts.push(AccountMeta::new(*raid_program, false));
+        accounts.push(AccountMeta::new(
+            pda::raid_program_token_account_address(program_id, raid_program).0,
+            false,
+        ));
+    }
+
+    build(
+        program_id,
+        BullPosterInstruction::FinalizeCompetition,
+        accounts,
+    )
+}
+
+pub fn check_raid_status(program_id: &Pubkey, competition: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::CheckRaidStatus,
+        vec![AccountMeta::new(*competition, false)],
+    )
+}
+
+pub fn fund_reward_vault(
+    program_id: &Pubkey,
+    funder: &Pubkey,
+    competition: &Pubkey,
+    reward_vault: &Pubkey,
+    funder_token_account: &Pubkey,
+    amount: u64,
+    mint: bool,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::FundRewardVault { amount, mint },
+        vec![
+            AccountMeta::new(*funder, true),
+            AccountMeta::new_readonly(*competition, false),
+            AccountMeta::new(*reward_vault, false),
+            AccountMeta::new(pda::token_mint_address(program_id).0, false),
+            AccountMeta::new(*funder_token_account, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+// `raids` holds a (raid card, raid program) pair per placement, best first
+pub fn distribute_rewards(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    competition: &Pubkey,
+    reward_vault: &Pubkey,
+    raids: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(*competition, false),
+        AccountMeta::new(*reward_vault, false),
+        AccountMeta::new(pda::claim_vault_address(program_id).0, false),
+        AccountMeta::new_readonly(pda::token_mint_address(program_id).0, false),
+        AccountMeta::new_readonly(spl_token_2022::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+    ];
+    for (raid_card, raid_program) in raids {
+        accounts.push(AccountMeta::new(*raid_card, false));
+        accounts.push(AccountMeta::new(*raid_program, false));
+    }
+
+    build(
+        program_id,
+        BullPosterInstruction::DistributeRewards,
+        accounts,
+    )
+}
+
+// `user_cards` must follow the raid card's placements, matching `engagement_scores`
+pub fn credit_raid_rewards(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    raid_card: &Pubkey,
+    users: &[Pubkey],
+    engagement_scores: Vec<u64>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(*raid_card, false),
+        AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for user in users {
+        accounts.push(AccountMeta::new(
+            pda::user_card_address(program_id, user).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(
+            pda::burn_ledger_address(program_id, user, raid_card).0,
+            false,
+        ));
+    }
+
+    build(
+        program_id,
+        BullPosterInstruction::CreditRaidRewards { engagement_scores },
+        accounts,
+    )
+}
+
+pub fn claim_rewards(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    user_card: &Pubkey,
+    user_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::ClaimRewards,
+        vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(*user_card, false),
+            AccountMeta::new(pda::claim_vault_address(program_id).0, false),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new_readonly(pda::token_mint_address(program_id).0, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn post_merkle_root(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    competition: &Pubkey,
+    distributor: &Pubkey,
+    merkle_root: [u8; 32],
+    total_amount: u64,
+    leaf_count: u64,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::PostMerkleRoot {
+            merkle_root,
+            total_amount,
+            leaf_count,
+        },
+        vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*competition, false),
+            AccountMeta::new(*distributor, false),
+            AccountMeta::new_readonly(pda::reward_vault_address(program_id, competition).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn claim_with_proof(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    user_card: &Pubkey,
+    competition: &Pubkey,
+    distributor: &Pubkey,
+    reward_vault: &Pubkey,
+    user_token_account: &Pubkey,
+    index: u64,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::ClaimWithProof {
+            index,
+            amount,
+            proof,
+        },
+        vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(*user_card, false),
+            AccountMeta::new(*competition, false),
+            AccountMeta::new(*distributor, false),
+            AccountMeta::new(*reward_vault, false),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new_readonly(pda::token_mint_address(program_id).0, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn request_unstake(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    raid_program: &Pubkey,
+    raid_program_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::RequestUnstake { amount },
+        vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(*raid_program, false),
+            AccountMeta::new_readonly(*raid_program_token_account, false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn withdraw_stake(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    raid_program: &Pubkey,
+    raid_program_token_account: &Pubkey,
+    user_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::WithdrawStake,
+        vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(*raid_program, false),
+            AccountMeta::new(*raid_program_token_account, false),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new_readonly(pda::token_mint_address(program_id).0, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn add_stake(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    raid_program: &Pubkey,
+    raid_program_token_account: &Pubkey,
+    user_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::AddStake { amount },
+        vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(*raid_program, false),
+            AccountMeta::new(*raid_program_token_account, false),
+            AccountMeta::new(*user_token_account, false),
+            AccountMeta::new_readonly(pda::token_mint_address(program_id).0, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+// Only the tunable parameters are read from `params`; the authority, pause state and
+// mint totals are kept by the program
+pub fn update_config(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    params: &GlobalConfig,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::UpdateConfig {
+            required_stake_amount: params.required_stake_amount,
+            silver_stake_amount: params.silver_stake_amount,
+            gold_stake_amount: params.gold_stake_amount,
+            unstake_cooldown: params.unstake_cooldown,
+            slash_basis_points: params.slash_basis_points,
+            raid_start_delay: params.raid_start_delay,
+            raid_duration: params.raid_duration,
+            pvp_acceptance_window: params.pvp_acceptance_window,
+            reward_caps: params.reward_caps,
+            max_supply: params.max_supply,
+            epoch_emission_budget: params.epoch_emission_budget,
+            epoch_length: params.epoch_length,
+            emission_threshold: params.emission_threshold,
+        },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn propose_authority(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    new_authority: Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::ProposeAuthority { new_authority },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn accept_authority(program_id: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::AcceptAuthority,
+        vec![
+            AccountMeta::new_readonly(*new_authority, true),
+            AccountMeta::new(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn set_pause(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    paused: bool,
+    paused_instructions: u64,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::SetPause {
+            paused,
+            paused_instructions,
+        },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn resize_account(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    target: &Pubkey,
+    new_len: u64,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::ResizeAccount { new_len },
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*target, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn leave_competition(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    raid_program: &Pubkey,
+    competition: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::LeaveCompetition,
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(*raid_program, false),
+            AccountMeta::new(*competition, false),
+            AccountMeta::new(
+                pda::raid_card_address(program_id, competition, raid_program).0,
+                false,
+            ),
+            AccountMeta::new_readonly(pda::config_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn migrate_account(program_id: &Pubkey, payer: &Pubkey, target: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        BullPosterInstruction::MigrateAccount,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*target, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+// MigrateAccount for the legacy program state, which also moves the legacy registries
+// onto page 0 of the paged ones
+pub fn migrate_legacy_program_state(program_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(pda::program_state_address(program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for (legacy_seed, seed) in [
+        (
+            legacy::RAID_PROGRAMS_STATE_SEED,
+            pda::RAID_PROGRAMS_REGISTRY_SEED,
+        ),
+        (legacy::RAIDS_STATE_SEED, pda::RAIDS_REGISTRY_SEED),
+        (
+            legacy::COMPETITIONS_STATE_SEED,
+            pda::COMPETITIONS_REGISTRY_SEED,
+        ),
+    ] {
+        accounts.push(AccountMeta::new_readonly(
+            Pubkey::find_program_address(&[legacy_seed], program_id).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            pda::registry_page_address(program_id, seed, 0).0,
+            false,
+        ));
+    }
+
+    build(program_id, BullPosterInstruction::MigrateAccount, accounts)
+}
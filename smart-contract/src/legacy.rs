@@ -0,0 +1,199 @@
+// Account layouts written before cards had a header, read only by MigrateAccount.
+//
+// These accounts start straight with their first field, so their card type is told
+// from their address: each type is at a PDA derived from fields it stores. Lists
+// were kept as strings, either comma separated pubkeys or JSON, and are parsed into
+// the vectors the current cards use. Every layout here is plain Borsh followed by
+// zero padding up to the account size.
+
+use crate::{competition_type_index, LastSeenRaid};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{msg, program_error::ProgramError, pubkey::Pubkey};
+use std::str::FromStr;
+
+// The three registries were single accounts, each holding one comma separated list
+pub const RAID_PROGRAMS_STATE_SEED: &[u8] = b"program_raid_programs_state";
+pub const RAIDS_STATE_SEED: &[u8] = b"program_raids_state";
+pub const COMPETITIONS_STATE_SEED: &[u8] = b"program_competitions_state";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ProgramStateCard {
+    pub last_seen_raids: String, // JSON: { "<type>": { "sequence": n, "competition_id": [seed bytes] } }
+    pub registered_programs_count: u64,
+    pub registered_users_count: u64,
+}
+
+// Layout shared by the raid programs, raids and competitions registries
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct RegistryCard {
+    pub pubkeys: String, // Comma separated
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct RaidCard {
+    pub competition_id: Pubkey,
+    pub raid_program_id: Pubkey,
+    pub raid_id: Pubkey,
+    pub distributed_rewards: String, // JSON: { "<user>": rewards }
+    pub placements: String,          // JSON: ["<user>", ...]
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct CompetitionCard {
+    pub competition_id: Pubkey,
+    pub competition_type: String,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub total_rewards_distributed: u64,
+    pub status: String,
+    pub enrolled_programs: String, // Comma separated raid programs
+    pub required_programs: u64,
+    pub challenger_program_id: Option<Pubkey>,
+    pub challenged_program_id: Option<Pubkey>,
+    pub start_expiration: Option<u64>,
+    pub distributed_rewards: String, // JSON: { "<raid card>": rewards }
+    pub placements: String,          // JSON: ["<raid card>", ...]
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct RaidProgramCard {
+    pub raid_program_id: Pubkey,
+    pub name: String,
+    pub description: String,
+    pub user_key: Pubkey,
+    pub profile_picture_url: String,
+    pub pvp_requests: String, // Comma separated
+    pub raids: String,        // Comma separated
+    pub is_conducting_raid: bool,
+    pub active_raid_id: Pubkey,
+    pub size: u64,
+    pub total_rewards_distributed: u64,
+    pub total_raid_wins: u64,
+    pub total_raids_partaken: u64,
+    pub program_rank: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct UserCard {
+    pub user_pubkey: Pubkey,
+    pub owned_programs: String,    // Comma separated
+    pub enrolled_programs: String, // Comma separated
+    pub is_conducting_raid: bool,
+    pub user_email: String,
+    pub user_dob: String,
+    pub user_twitter_handle: String,
+    pub total_rewards: u64,
+    pub participated_raids: u64,
+    pub raid_ranking: u64,
+    pub engagement_score: u64,
+    pub streaks: u64,
+    pub profile_picture_url: String,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ProgramLeaderboardStateCard {
+    pub leaderboard_data: String,
+}
+
+// Read a legacy layout, ignoring the zero padding after it
+pub fn read<T: BorshDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+    T::deserialize(&mut &data[..]).map_err(|e| {
+        msg!(
+            "Failed to deserialize legacy {}: {:?}",
+            std::any::type_name::<T>(),
+            e
+        );
+        ProgramError::InvalidAccountData
+    })
+}
+
+fn parse_pubkey(key: &str) -> Result<Pubkey, ProgramError> {
+    Pubkey::from_str(key.trim()).map_err(|_| {
+        msg!("Invalid pubkey {:?} in legacy list", key);
+        ProgramError::InvalidAccountData
+    })
+}
+
+fn parse_json(json: &str) -> Result<Option<serde_json::Value>, ProgramError> {
+    if json.trim().is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(json).map(Some).map_err(|e| {
+        msg!("Invalid legacy JSON: {}", e);
+        ProgramError::InvalidAccountData
+    })
+}
+
+// "a,b,c," as written by the old handlers, which left a trailing comma
+pub fn parse_pubkey_list(list: &str) -> Result<Vec<Pubkey>, ProgramError> {
+    list.split(',')
+        .filter(|key| !key.trim().is_empty())
+        .map(parse_pubkey)
+        .collect()
+}
+
+// { "<pubkey>": rewards, ... }, empty when nothing was distributed
+pub fn parse_rewards(json: &str) -> Result<Vec<(Pubkey, u64)>, ProgramError> {
+    let value = match parse_json(json)? {
+        Some(value) => value,
+        None => return Ok(Vec::new()),
+    };
+    let entries = value.as_object().ok_or(ProgramError::InvalidAccountData)?;
+    entries
+        .iter()
+        .map(|(key, rewards)| {
+            Ok((
+                parse_pubkey(key)?,
+                rewards.as_u64().ok_or(ProgramError::InvalidAccountData)?,
+            ))
+        })
+        .collect()
+}
+
+// ["<pubkey>", ...], best first, empty before placements were decided
+pub fn parse_placements(json: &str) -> Result<Vec<Pubkey>, ProgramError> {
+    let value = match parse_json(json)? {
+        Some(value) => value,
+        None => return Ok(Vec::new()),
+    };
+    let entries = value.as_array().ok_or(ProgramError::InvalidAccountData)?;
+    entries
+        .iter()
+        .map(|key| parse_pubkey(key.as_str().ok_or(ProgramError::InvalidAccountData)?))
+        .collect()
+}
+
+// The old program state kept the hashed competition seed rather than the
+// competition's address, so the address is derived from it here
+pub fn parse_last_seen_raids(
+    program_id: &Pubkey,
+    json: &str,
+) -> Result<[LastSeenRaid; 5], ProgramError> {
+    let mut last_seen_raids = [LastSeenRaid::default(); 5];
+    let value = match parse_json(json)? {
+        Some(value) => value,
+        None => return Ok(last_seen_raids),
+    };
+    let entries = value.as_object().ok_or(ProgramError::InvalidAccountData)?;
+    for (competition_type, entry) in entries {
+        let sequence = entry["sequence"]
+            .as_u64()
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let seed = entry["competition_id"]
+            .as_array()
+            .ok_or(ProgramError::InvalidAccountData)?
+            .iter()
+            .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or(ProgramError::InvalidAccountData)?;
+        if seed.len() != 32 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        last_seen_raids[competition_type_index(competition_type)?] = LastSeenRaid {
+            sequence,
+            competition_id: Pubkey::find_program_address(&[&seed], program_id).0,
+        };
+    }
+    Ok(last_seen_raids)
+}
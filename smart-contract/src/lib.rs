@@ -1,36 +1,234 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use serde_json::json;
-use sha2::{Digest, Sha256};
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
 use solana_program::entrypoint;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     clock::Clock,
-    entrypoint::ProgramResult,
+    decode_error::DecodeError,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
     msg,
     program::{invoke, invoke_signed},
-    program_error::ProgramError,
+    program_error::{PrintProgramError, ProgramError},
     program_pack::Pack,
     pubkey::Pubkey,
+    system_instruction,
     sysvar::{rent::Rent, Sysvar},
 };
 use spl_associated_token_account::tools::account::create_pda_account;
-use spl_token_2022::{instruction as token_instruction, state::Account, state::Mint};
+use spl_token_2022::{
+    extension::StateWithExtensions, instruction as token_instruction, state::Account, state::Mint,
+};
 use std::io::{Cursor, Read, Write};
+use thiserror::Error;
+
+#[cfg(feature = "no-entrypoint")]
+pub mod instruction;
+pub mod legacy;
+pub mod merkle;
+pub mod pda;
+#[cfg(test)]
+mod test_utils;
+pub mod validation;
+
+use validation::ValidateAccount;
 
 // Define your program ID
 solana_program::declare_id!("FY9aF1jszyGoABygvsQ28oHfqgyUVZkttzr8Vcx7sLKH");
 
 // Constants
+// Only this key may run InitializeProgram and InitializeConfig; afterwards the authority
+// lives in GlobalConfig
 const AUTHORITY_PUBKEY: Pubkey =
     solana_program::pubkey!("3tXoH9Vy1Ah6UzmS4byEVdi7ouHvaARY5XWkyGtVHZm8");
-const REQUIRED_STAKE_AMOUNT: u64 = 1_000 * 1_000_000_000; // 1000 tokens with 9 decimals
 
+const ACCOUNT_GROWTH_STEP: usize = 1000; // Accounts grow in steps of this many bytes when a list outgrows them
+
+// Registries are split into pages (see pda::registry_page_address), so no single account
+// has to hold every entry
+pub const REGISTRY_PAGE_CAPACITY: u64 = 30; // 30 pubkeys fit in the standard 1000 byte account
+
+// Defaults written to GlobalConfig by InitializeProgram and InitializeConfig
+const DEFAULT_REQUIRED_STAKE_AMOUNT: u64 = 1_000 * 1_000_000_000; // 1000 tokens with 9 decimals
+const DEFAULT_SILVER_STAKE_AMOUNT: u64 = 5_000 * 1_000_000_000; // 5000 tokens with 9 decimals
+const DEFAULT_GOLD_STAKE_AMOUNT: u64 = 10_000 * 1_000_000_000; // 10000 tokens with 9 decimals
+const DEFAULT_UNSTAKE_COOLDOWN: u64 = 3 * 24 * 60 * 60; // 3 days between requesting and withdrawing stake
+const DEFAULT_SLASH_BASIS_POINTS: u64 = 1_000; // 10% of stake lost for abandoning a raid
+const DEFAULT_RAID_START_DELAY: u64 = 300; // 5 minutes between a competition filling up and starting
+const DEFAULT_RAID_DURATION: u64 = 1_200; // 20 minutes of raiding
+const DEFAULT_PVP_ACCEPTANCE_WINDOW: u64 = 300; // 5 minutes for the challenged program to accept
+const DEFAULT_MAX_SUPPLY: u64 = 1_000_000_000 * 1_000_000_000; // 1 billion tokens with 9 decimals
+const DEFAULT_EPOCH_EMISSION_BUDGET: u64 = 1_000_000 * 1_000_000_000; // 1 million tokens per epoch
+const DEFAULT_EPOCH_LENGTH: u64 = 24 * 60 * 60; // 1 day
+const DEFAULT_EMISSION_THRESHOLD: u64 = 500_000 * 1_000_000_000; // Epoch emissions above this shrink new reward caps
+const DEFAULT_REWARD_CAPS: [u64; 5] = [
+    1_000 * 1_000_000_000, // PvP
+    2_000 * 1_000_000_000, // 4-program
+    3_000 * 1_000_000_000, // 6-program
+    4_000 * 1_000_000_000, // 12-program
+    5_000 * 1_000_000_000, // 24-program
+];
+
+// Error codes are part of the program's interface: append new variants with the next
+// free number and never renumber or reuse an existing one
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Error, FromPrimitive)]
 pub enum BullPosterError {
-    InvalidCompetitionStatus,
-    CompetitionFull,
-    UserAlreadyEnrolled,
-    InsufficientTokens,
-    // Add more as needed
+    // Existing codes
+    #[error("Competition is not in the required status")]
+    InvalidCompetitionStatus = 0,
+    #[error("Competition is full")]
+    CompetitionFull = 1,
+    #[error("Already enrolled")]
+    UserAlreadyEnrolled = 2,
+    #[error("Insufficient tokens")]
+    InsufficientTokens = 3,
+    #[error("Program is paused")]
+    ProgramPaused = 4,
+    #[error("Emission limit exceeded")]
+    EmissionLimitExceeded = 5,
+    #[error("Account discriminator does not match the expected card type")]
+    InvalidAccountDiscriminator = 6,
+    #[error("Account layout version is not supported")]
+    UnsupportedAccountVersion = 7,
+
+    // Authority
+    #[error("Signer is not the program authority")]
+    Unauthorized = 8,
+    #[error("Signer is not the pending authority")]
+    NotPendingAuthority = 9,
+    #[error("Invalid config parameters")]
+    InvalidConfigParameters = 10,
+
+    // Account addresses and ownership
+    #[error("Account is not owned by this program")]
+    InvalidAccountOwner = 11,
+    #[error("Global config account does not match the derived PDA")]
+    InvalidConfigAccount = 12,
+    #[error("Program state account does not match the derived PDA")]
+    InvalidProgramStateAccount = 13,
+    #[error("Leaderboard account does not match the derived PDA")]
+    InvalidLeaderboardAccount = 14,
+    #[error("Token mint does not match the derived PDA")]
+    InvalidTokenMint = 15,
+    #[error("User card account does not match the derived PDA")]
+    InvalidUserCardAccount = 16,
+    #[error("Raid program account does not match the derived PDA")]
+    InvalidRaidProgramAccount = 17,
+    #[error("Stake account does not match the derived PDA")]
+    InvalidStakeAccount = 18,
+    #[error("Competition account does not match the derived PDA")]
+    InvalidCompetitionAccount = 19,
+    #[error("Raid card account does not match the derived PDA")]
+    InvalidRaidCardAccount = 20,
+    #[error("Burn ledger account does not match the derived PDA")]
+    InvalidBurnLedgerAccount = 21,
+    #[error("Reward vault does not match the derived PDA")]
+    InvalidRewardVault = 22,
+    #[error("Claim vault does not match the derived PDA")]
+    InvalidClaimVault = 23,
+    #[error("Merkle distributor account does not match the derived PDA")]
+    InvalidDistributorAccount = 24,
+    #[error("Registry page does not match the derived PDA")]
+    InvalidRegistryPage = 25,
+
+    // Accounts that must not exist yet
+    #[error("Raid program account already exists")]
+    RaidProgramAlreadyExists = 26,
+    #[error("User card already exists")]
+    UserCardAlreadyExists = 27,
+    #[error("Raid card already exists")]
+    RaidCardAlreadyExists = 28,
+    #[error("Competition already exists")]
+    CompetitionAlreadyExists = 29,
+    // No longer returned, burns accumulate in one ledger per user and raid
+    #[error("Burn card already exists")]
+    BurnCardAlreadyExists = 30,
+    #[error("Merkle root already posted for this competition")]
+    MerkleRootAlreadyPosted = 31,
+    #[error("Global config already exists")]
+    ConfigAlreadyExists = 65,
+
+    // Input validation
+    #[error("Input strings exceed maximum allowed length")]
+    InputTooLong = 32,
+    #[error("Unknown competition type")]
+    InvalidCompetitionType = 33,
+    #[error("Amount must be greater than zero")]
+    ZeroAmount = 34,
+
+    // Raid programs and user cards
+    #[error("Signer does not own the raid program")]
+    NotRaidProgramOwner = 35,
+    #[error("Signer does not own the user card")]
+    NotUserCardOwner = 36,
+    #[error("Raid program is deactivated")]
+    RaidProgramDeactivated = 37,
+    #[error("User is not enrolled in the raid program")]
+    UserNotEnrolled = 38,
+
+    // Staking
+    #[error("No unstake has been requested")]
+    NoPendingUnstake = 39,
+    #[error("Unstake cooldown has not elapsed")]
+    UnstakeCooldownActive = 40,
+    #[error("Stake is locked while the program is conducting a raid")]
+    StakeLockedDuringRaid = 41,
+
+    // Competitions and raids
+    #[error("Raid program is not enrolled in the competition")]
+    RaidProgramNotEnrolled = 42,
+    #[error("Competition has ended")]
+    CompetitionEnded = 43,
+    #[error("Current competition account does not exist")]
+    CompetitionNotFound = 44,
+    #[error("A raid program cannot challenge itself")]
+    CannotChallengeSelf = 45,
+    #[error("Only the challenged program may accept")]
+    NotChallengedProgram = 46,
+    #[error("PvP challenge has expired")]
+    ChallengeExpired = 47,
+    #[error("Raid card does not belong to this competition and raid program")]
+    RaidCardMismatch = 48,
+    #[error("Raid program is listed more than once")]
+    DuplicateRaidProgram = 49,
+    // No longer returned, finalize_competition slashes every abandoned raid
+    #[error("Raid cannot be slashed")]
+    RaidNotSlashable = 50,
+    #[error("Opponent does not match the challenged program")]
+    InvalidOpponent = 51,
+    // No longer returned, finalize_competition picks the destination itself
+    #[error("Slashed stake must go to the opponent's stake account or the reward vault")]
+    InvalidSlashDestination = 52,
+
+    // Rewards
+    #[error("No engagement, no rewards to credit")]
+    NoEngagement = 53,
+    #[error("User is listed more than once")]
+    DuplicateUser = 54,
+    #[error("Merkle distribution needs leaves and a non-zero total")]
+    InvalidMerkleDistribution = 55,
+    #[error("Merkle distributor does not belong to this competition")]
+    DistributorMismatch = 56,
+    #[error("Invalid merkle proof")]
+    InvalidMerkleProof = 57,
+    #[error("Merkle leaf already claimed")]
+    LeafAlreadyClaimed = 58,
+    #[error("Reward vault holds less than the merkle distribution total")]
+    RewardVaultUnderfunded = 64,
+
+    // Maintenance
+    #[error("Account is already large enough")]
+    AccountAlreadyLargeEnough = 59,
+
+    // Account validation
+    #[error("Account must be writable")]
+    AccountNotWritable = 60,
+    #[error("Token program must be Token-2022")]
+    InvalidTokenProgram = 61,
+    #[error("Invalid system program")]
+    InvalidSystemProgram = 62,
+    #[error("Invalid rent sysvar")]
+    InvalidRentSysvar = 63,
 }
 
 impl From<BullPosterError> for ProgramError {
@@ -39,72 +237,535 @@ impl From<BullPosterError> for ProgramError {
     }
 }
 
-// Instruction enum
+impl<T> DecodeError<T> for BullPosterError {
+    fn type_of() -> &'static str {
+        "BullPosterError"
+    }
+}
+
+impl PrintProgramError for BullPosterError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
+
+// Every stored card starts with an 8-byte type discriminator and a layout version
+// byte, so an account of one type cannot be passed off as another and old layouts
+// can be told apart from new ones
+pub const CARD_HEADER_LEN: usize = 9;
+
+// Version given to accounts written before cards had a header. Their body is the
+// whole account, in the layouts kept in the `legacy` module.
+pub const LEGACY_VERSION: u8 = 0;
+
+pub trait Card: BorshSerialize + Sized {
+    const DISCRIMINATOR: [u8; 8];
+    const VERSION: u8;
+
+    fn check_header(data: &[u8]) -> ProgramResult {
+        if data.len() < CARD_HEADER_LEN || data[..8] != Self::DISCRIMINATOR {
+            msg!("Account is not a {}", std::any::type_name::<Self>());
+            return Err(BullPosterError::InvalidAccountDiscriminator.into());
+        }
+        if data[8] != Self::VERSION {
+            msg!(
+                "Unsupported {} layout version {}, expected {}",
+                std::any::type_name::<Self>(),
+                data[8],
+                Self::VERSION
+            );
+            return Err(BullPosterError::UnsupportedAccountVersion.into());
+        }
+        Ok(())
+    }
+
+    fn write_header(cursor: &mut Cursor<&mut [u8]>) -> ProgramResult {
+        cursor
+            .write_all(&Self::DISCRIMINATOR)
+            .and_then(|_| cursor.write_all(&[Self::VERSION]))
+            .map_err(|_| ProgramError::AccountDataTooSmall)
+    }
+
+    // Bytes needed to store the card, header included
+    fn stored_len(&self) -> Result<usize, ProgramError> {
+        Ok(CARD_HEADER_LEN + self.try_to_vec()?.len())
+    }
+
+    // Plain Borsh reads and writes for cards without a custom layout
+    fn read_card(data: &[u8]) -> Result<Self, ProgramError>
+    where
+        Self: BorshDeserialize,
+    {
+        Self::check_header(data)?;
+        Self::deserialize(&mut &data[CARD_HEADER_LEN..]).map_err(|e| {
+            msg!(
+                "Failed to deserialize {}: {:?}",
+                std::any::type_name::<Self>(),
+                e
+            );
+            ProgramError::InvalidAccountData
+        })
+    }
+
+    fn write_card(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
+        let mut cursor = Cursor::new(buffer);
+        Self::write_header(&mut cursor)?;
+        self.serialize(&mut cursor)
+            .map_err(|_| ProgramError::AccountDataTooSmall)?;
+        Ok(cursor.position() as usize)
+    }
+
+    // Write the card in its current layout; cards with a custom layout override this
+    fn pack_into(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
+        self.write_card(buffer)
+    }
+
+    // Rebuild the card stored at `address` from the body of an older layout, read by
+    // MigrateAccount. When a card's VERSION is bumped, its impl converts each earlier
+    // version here, and cards that existed before headers also convert
+    // LEGACY_VERSION. Version 1 is the first versioned layout, so by default there
+    // is nothing to convert from.
+    fn upgrade(
+        _program_id: &Pubkey,
+        _address: &Pubkey,
+        version: u8,
+        _body: &[u8],
+    ) -> Result<Self, ProgramError> {
+        msg!(
+            "No migration path for {} version {}",
+            std::any::type_name::<Self>(),
+            version
+        );
+        Err(BullPosterError::UnsupportedAccountVersion.into())
+    }
+
+    // Give an older body the current header and `added_len` zero bytes, so the current
+    // reader can parse a layout that only gained trailing fields since. The upgrade
+    // then fills in the added fields.
+    fn with_current_header(body: &[u8], added_len: usize) -> Vec<u8> {
+        let mut data = Vec::with_capacity(CARD_HEADER_LEN + body.len() + added_len);
+        data.extend_from_slice(&Self::DISCRIMINATOR);
+        data.push(Self::VERSION);
+        data.extend_from_slice(body);
+        data.resize(data.len() + added_len, 0);
+        data
+    }
+}
+
+// Stake tiers decide which competition types a raid program may enter
+#[derive(Debug, PartialEq, PartialOrd)]
+pub enum StakeTier {
+    Unranked,
+    Bronze,
+    Silver,
+    Gold,
+}
+
+impl StakeTier {
+    pub fn from_stake(staked_amount: u64, config: &GlobalConfig) -> Self {
+        if staked_amount >= config.gold_stake_amount {
+            StakeTier::Gold
+        } else if staked_amount >= config.silver_stake_amount {
+            StakeTier::Silver
+        } else if staked_amount >= config.required_stake_amount {
+            StakeTier::Bronze
+        } else {
+            StakeTier::Unranked
+        }
+    }
+
+    pub fn required_for(competition_type: &str) -> Result<Self, ProgramError> {
+        match competition_type {
+            "PvP" | "4-program" => Ok(StakeTier::Bronze),
+            "6-program" | "12-program" => Ok(StakeTier::Silver),
+            "24-program" => Ok(StakeTier::Gold),
+            _ => Err(BullPosterError::InvalidCompetitionType.into()),
+        }
+    }
+}
+
+// Position of a competition type in per-type arrays such as reward caps and last seen raids
+pub fn competition_type_index(competition_type: &str) -> Result<usize, ProgramError> {
+    match competition_type {
+        "PvP" => Ok(0),
+        "4-program" => Ok(1),
+        "6-program" => Ok(2),
+        "12-program" => Ok(3),
+        "24-program" => Ok(4),
+        _ => Err(BullPosterError::InvalidCompetitionType.into()),
+    }
+}
+
+// Instruction enum. Instruction data is the Borsh encoding of a variant, so the
+// leading byte is the variant index noted beside each one.
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub enum BullPosterInstruction {
-    InitializeProgram, // 1
+    InitializeProgram {
+        // 0
+        initial_authority: Option<Pubkey>,
+    },
     CreateRaidProgram {
-        // 2
+        // 1
         name: String,
         description: String,
         profile_picture_url: String,
     },
     AuthorityMint {
         amount: u64,
-    }, // 3
+    }, // 2
     CreateRaid {
         competition_type: String,
-    }, // 4
-    AcceptPVPRequest, // 5
+    }, // 3
+    AcceptPVPRequest, // 4
     BurnTokens {
         burn_amount: u64,
-    }, // 6
-    EnrollInProgram,  // 7
-    CreateUserCard,   // 8
+    }, // 5
+    EnrollInProgram,  // 6
+    CreateUserCard,   // 7
     UpdateUserCard {
-        // 9
+        // 8
         user_email: String,
         user_twitter_handle: String,
         user_dob: String,
         profile_picture_url: String,
     },
+    FinalizeCompetition, // 9
+    CheckRaidStatus,     // 10
+    FundRewardVault {
+        // 11
+        amount: u64,
+        mint: bool,
+    },
+    DistributeRewards, // 12
+    CreditRaidRewards {
+        // 13
+        engagement_scores: Vec<u64>,
+    },
+    ClaimRewards, // 14
+    PostMerkleRoot {
+        // 15
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        leaf_count: u64,
+    },
+    ClaimWithProof {
+        // 16
+        index: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    },
+    RequestUnstake {
+        // 17
+        amount: u64,
+    },
+    WithdrawStake, // 18
+    AddStake {
+        // 19
+        amount: u64,
+    },
+    UpdateConfig {
+        // 20
+        required_stake_amount: u64,
+        silver_stake_amount: u64,
+        gold_stake_amount: u64,
+        unstake_cooldown: u64,
+        slash_basis_points: u64,
+        raid_start_delay: u64,
+        raid_duration: u64,
+        pvp_acceptance_window: u64,
+        reward_caps: [u64; 5],
+        max_supply: u64,
+        epoch_emission_budget: u64,
+        epoch_length: u64,
+        emission_threshold: u64,
+    },
+    ProposeAuthority {
+        // 21
+        new_authority: Pubkey,
+    },
+    AcceptAuthority, // 22
+    SetPause {
+        // 23
+        paused: bool,
+        paused_instructions: u64,
+    },
+    ResizeAccount {
+        // 24
+        new_len: u64,
+    },
+    MigrateAccount, // 25
+    // Creates GlobalConfig on a deployment initialized before the config existed
+    InitializeConfig {
+        // 26
+        initial_authority: Option<Pubkey>,
+    },
+    LeaveCompetition, // 27
+}
+
+impl BullPosterInstruction {
+    // Decode instruction data; trailing bytes are rejected
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        Self::try_from_slice(input).map_err(|e| {
+            msg!("Failed to decode instruction: {:?}", e);
+            ProgramError::InvalidInstructionData
+        })
+    }
+
+    // Encode the instruction the way `unpack` expects it
+    pub fn pack(&self) -> Vec<u8> {
+        self.try_to_vec()
+            .expect("Serializing an instruction into a Vec cannot fail")
+    }
+}
+
+// Program-wide parameters, stored in the b"global_config" PDA
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
+pub struct GlobalConfig {
+    pub authority: Pubkey, // Signs UpdateConfig and the other authority-only instructions
+    pub required_stake_amount: u64, // Minimum stake to register a raid program (Bronze tier)
+    pub silver_stake_amount: u64, // Stake needed for Silver tier competitions
+    pub gold_stake_amount: u64, // Stake needed for Gold tier competitions
+    pub unstake_cooldown: u64, // Seconds between RequestUnstake and WithdrawStake
+    pub slash_basis_points: u64, // Share of stake slashed for abandoning a raid
+    pub raid_start_delay: u64, // Seconds between a competition filling up and starting
+    pub raid_duration: u64, // Seconds a competition runs once started
+    pub pvp_acceptance_window: u64, // Seconds a challenged program has to accept a PvP raid
+    pub reward_caps: [u64; 5], // Max reward pool per competition type: PvP, 4, 6, 12, 24
+    pub pending_authority: Pubkey, // Proposed authority awaiting AcceptAuthority, default when none
+    pub paused: bool,      // Stops every pausable instruction
+    pub paused_instructions: u64, // Bit N set stops instruction N only
+    pub max_supply: u64,   // Most tokens that may ever be minted
+    pub epoch_emission_budget: u64, // Most tokens that may be minted per epoch
+    pub epoch_length: u64, // Seconds per emission epoch
+    pub total_minted: u64, // Tokens minted since the config was created
+    pub epoch_start: u64,  // Start of the current emission epoch
+    pub epoch_minted: u64, // Tokens minted in the current emission epoch
+    pub emission_threshold: u64, // Epoch emissions above which new competitions get smaller caps
+    pub bump: u8,          // Canonical bump of the config PDA
+    pub token_mint_bump: u8, // Canonical bump of the token mint PDA
+    pub claim_vault_bump: u8, // Canonical bump of the reward claim vault PDA
+}
+
+impl Card for GlobalConfig {
+    const DISCRIMINATOR: [u8; 8] = *b"GLOBLCFG";
+    const VERSION: u8 = 2;
+
+    // Version 2 added the bumps of the config, token mint and claim vault PDAs
+    fn upgrade(
+        program_id: &Pubkey,
+        _address: &Pubkey,
+        version: u8,
+        body: &[u8],
+    ) -> Result<Self, ProgramError> {
+        if version != 1 {
+            return Err(BullPosterError::UnsupportedAccountVersion.into());
+        }
+        let mut config = Self::read_card(&Self::with_current_header(body, 3))?;
+        config.bump = pda::config_address(program_id).1;
+        config.token_mint_bump = pda::token_mint_address(program_id).1;
+        config.claim_vault_bump = pda::claim_vault_address(program_id).1;
+        Ok(config)
+    }
+}
+
+impl GlobalConfig {
+    // The config a fresh deployment starts with, its first epoch beginning at epoch_start
+    pub fn with_defaults(program_id: &Pubkey, authority: Pubkey, epoch_start: u64) -> Self {
+        GlobalConfig {
+            authority,
+            required_stake_amount: DEFAULT_REQUIRED_STAKE_AMOUNT,
+            silver_stake_amount: DEFAULT_SILVER_STAKE_AMOUNT,
+            gold_stake_amount: DEFAULT_GOLD_STAKE_AMOUNT,
+            unstake_cooldown: DEFAULT_UNSTAKE_COOLDOWN,
+            slash_basis_points: DEFAULT_SLASH_BASIS_POINTS,
+            raid_start_delay: DEFAULT_RAID_START_DELAY,
+            raid_duration: DEFAULT_RAID_DURATION,
+            pvp_acceptance_window: DEFAULT_PVP_ACCEPTANCE_WINDOW,
+            reward_caps: DEFAULT_REWARD_CAPS,
+            pending_authority: Pubkey::default(),
+            paused: false,
+            paused_instructions: 0,
+            max_supply: DEFAULT_MAX_SUPPLY,
+            epoch_emission_budget: DEFAULT_EPOCH_EMISSION_BUDGET,
+            epoch_length: DEFAULT_EPOCH_LENGTH,
+            total_minted: 0,
+            epoch_start,
+            epoch_minted: 0,
+            emission_threshold: DEFAULT_EMISSION_THRESHOLD,
+            bump: pda::config_address(program_id).1,
+            token_mint_bump: pda::token_mint_address(program_id).1,
+            claim_vault_bump: pda::claim_vault_address(program_id).1,
+        }
+    }
+
+    // Read the config, checking that the account really is the config PDA
+    pub fn load(program_id: &Pubkey, config_account: &AccountInfo) -> Result<Self, ProgramError> {
+        config_account.card::<GlobalConfig>(program_id)?;
+        let config = GlobalConfig::read_card(&config_account.data.borrow())?;
+        let config_pda = pda::create_address(program_id, &[pda::CONFIG_SEED], config.bump)?;
+        config_account.address(&config_pda, BullPosterError::InvalidConfigAccount)?;
+
+        Ok(config)
+    }
+
+    // Account for a mint against the supply cap and the epoch budget, starting a
+    // new epoch once the current one has run out. The caller writes the config back.
+    pub fn record_mint(&mut self, amount: u64, now: u64) -> ProgramResult {
+        if now >= self.epoch_start.saturating_add(self.epoch_length) {
+            self.epoch_start = now;
+            self.epoch_minted = 0;
+        }
+
+        let total_minted = self
+            .total_minted
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if total_minted > self.max_supply {
+            msg!(
+                "Minting {} would exceed the max supply of {} ({} already minted)",
+                amount,
+                self.max_supply,
+                self.total_minted
+            );
+            return Err(BullPosterError::EmissionLimitExceeded.into());
+        }
+
+        let epoch_minted = self
+            .epoch_minted
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if epoch_minted > self.epoch_emission_budget {
+            msg!(
+                "Minting {} would exceed the epoch budget of {} ({} already minted this epoch)",
+                amount,
+                self.epoch_emission_budget,
+                self.epoch_minted
+            );
+            return Err(BullPosterError::EmissionLimitExceeded.into());
+        }
+
+        self.total_minted = total_minted;
+        self.epoch_minted = epoch_minted;
+        Ok(())
+    }
+
+    pub fn reward_cap_for(&self, competition_type: &str) -> Result<u64, ProgramError> {
+        Ok(self.reward_caps[competition_type_index(competition_type)?])
+    }
+
+    // Reward cap for a new competition: the base cap for its type, scaled down by
+    // threshold / emitted once this epoch's emissions pass the threshold, and never
+    // below a tenth of the base
+    pub fn competition_reward_cap(
+        &self,
+        competition_type: &str,
+        now: u64,
+    ) -> Result<u64, ProgramError> {
+        let base_cap = self.reward_cap_for(competition_type)?;
+
+        let recent_emissions = if now < self.epoch_start.saturating_add(self.epoch_length) {
+            self.epoch_minted
+        } else {
+            0
+        };
+        if recent_emissions <= self.emission_threshold {
+            return Ok(base_cap);
+        }
+
+        let scaled_cap =
+            (base_cap as u128 * self.emission_threshold as u128 / recent_emissions as u128) as u64;
+        Ok(scaled_cap.max(base_cap / 10))
+    }
+}
+
+// Latest competition opened for one competition type
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default)]
+pub struct LastSeenRaid {
+    pub sequence: u64,
+    pub competition_id: Pubkey,
 }
 
 #[derive(BorshSerialize, Debug)]
 pub struct ProgramStateCard {
-    pub last_seen_raids: String, // JSON string with sequence number and competition id per competition type
-    pub registered_programs_count: u64, // Number of registered programs
-    pub registered_users_count: u64, // Number of registered users
+    pub last_seen_raids: [LastSeenRaid; 5], // Indexed by competition_type_index: PvP, 4, 6, 12, 24
+    pub registered_programs_count: u64,     // Number of registered programs
+    pub registered_users_count: u64,        // Number of registered users
+    pub registered_raids_count: u64,        // Head of the raids registry
+    pub registered_competitions_count: u64, // Head of the competitions registry
+    pub bump: u8,                           // Canonical bump of the program state PDA
+}
+
+impl Card for ProgramStateCard {
+    const DISCRIMINATOR: [u8; 8] = *b"PROGSTAT";
+    const VERSION: u8 = 2;
+
+    fn pack_into(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
+        self.custom_serialize(buffer)
+    }
+
+    // The legacy layout kept last_seen_raids as JSON. Its registries were separate
+    // accounts, so the heads start at zero and MigrateAccount advances them as it
+    // moves the legacy registries into pages. Version 2 added the bump.
+    fn upgrade(
+        program_id: &Pubkey,
+        address: &Pubkey,
+        version: u8,
+        body: &[u8],
+    ) -> Result<Self, ProgramError> {
+        match version {
+            LEGACY_VERSION => {
+                let (program_state_pda, bump) = pda::program_state_address(program_id);
+                if address != &program_state_pda {
+                    return Err(BullPosterError::InvalidProgramStateAccount.into());
+                }
+                let legacy: legacy::ProgramStateCard = legacy::read(body)?;
+                Ok(ProgramStateCard {
+                    last_seen_raids: legacy::parse_last_seen_raids(
+                        program_id,
+                        &legacy.last_seen_raids,
+                    )?,
+                    registered_programs_count: 0,
+                    registered_users_count: legacy.registered_users_count,
+                    registered_raids_count: 0,
+                    registered_competitions_count: 0,
+                    bump,
+                })
+            }
+            1 => {
+                let (mut program_state, _) =
+                    Self::custom_deserialize(&Self::with_current_header(body, 1))?;
+                program_state.bump = pda::program_state_address(program_id).1;
+                Ok(program_state)
+            }
+            _ => Err(BullPosterError::UnsupportedAccountVersion.into()),
+        }
+    }
 }
 
 impl ProgramStateCard {
     // Custom deserialization with logging to track the process
     pub fn custom_deserialize(data: &[u8]) -> Result<(Self, usize), ProgramError> {
+        Self::check_header(data)?;
         let mut cursor = Cursor::new(data);
+        cursor.set_position(CARD_HEADER_LEN as u64);
 
         // Log the length of the input data (including extra space)
         msg!("Total data length: {} bytes", data.len());
 
-        // Deserialize `last_seen_raids` (string field)
-        let string_length =
-            u32::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
-                msg!("Failed to deserialize string length: {:?}", e);
-                ProgramError::InvalidAccountData
-            })?;
-        msg!("String length: {} bytes", string_length);
-
-        cursor.set_position(cursor.position() + 4); // Move cursor past the length field
-
-        let mut string_bytes = vec![0u8; string_length as usize];
-        cursor.read_exact(&mut string_bytes).map_err(|e| {
-            msg!("Failed to read string data: {:?}", e);
-            ProgramError::InvalidAccountData
-        })?;
-        let last_seen_raids = String::from_utf8(string_bytes).map_err(|e| {
-            msg!("Failed to convert string data: {:?}", e);
+        // Deserialize `last_seen_raids` (fixed array field)
+        let last_seen_raids = <[LastSeenRaid; 5]>::deserialize(
+            &mut &data[cursor.position() as usize..],
+        )
+        .map_err(|e| {
+            msg!("Failed to deserialize last_seen_raids: {:?}", e);
             ProgramError::InvalidAccountData
         })?;
-        msg!("Deserialized `last_seen_raids`: {}", last_seen_raids);
+        msg!("Deserialized `last_seen_raids`: {:?}", last_seen_raids);
+
+        cursor.set_position(cursor.position() + 5 * 40); // Move cursor past the array, 40 bytes per entry
 
         // Deserialize `registered_programs_count` (u64 field)
         let registered_programs_count = u64::deserialize(&mut &data[cursor.position() as usize..])
@@ -130,6 +791,45 @@ impl ProgramStateCard {
             registered_users_count
         );
 
+        cursor.set_position(cursor.position() + 8); // Move cursor past the u64 field
+
+        // Deserialize `registered_raids_count` (u64 field)
+        let registered_raids_count = u64::deserialize(&mut &data[cursor.position() as usize..])
+            .map_err(|e| {
+                msg!("Failed to deserialize registered_raids_count: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        msg!(
+            "Deserialized `registered_raids_count`: {}",
+            registered_raids_count
+        );
+
+        cursor.set_position(cursor.position() + 8); // Move cursor past the u64 field
+
+        // Deserialize `registered_competitions_count` (u64 field)
+        let registered_competitions_count =
+            u64::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+                msg!(
+                    "Failed to deserialize registered_competitions_count: {:?}",
+                    e
+                );
+                ProgramError::InvalidAccountData
+            })?;
+        msg!(
+            "Deserialized `registered_competitions_count`: {}",
+            registered_competitions_count
+        );
+
+        cursor.set_position(cursor.position() + 8); // Move cursor past the u64 field
+
+        // Deserialize `bump` (u8 field)
+        let bump = u8::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+            msg!("Failed to deserialize bump: {:?}", e);
+            ProgramError::InvalidAccountData
+        })?;
+
+        cursor.set_position(cursor.position() + 1); // Move cursor past the u8 field
+
         // Log the cursor's position after deserialization
         let position = cursor.position();
         msg!("Cursor position after deserialization: {} bytes", position);
@@ -146,6 +846,9 @@ impl ProgramStateCard {
                 last_seen_raids,
                 registered_programs_count,
                 registered_users_count,
+                registered_raids_count,
+                registered_competitions_count,
+                bump,
             },
             cursor.position() as usize,
         ))
@@ -154,15 +857,12 @@ impl ProgramStateCard {
     // Custom serialization
     pub fn custom_serialize(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
         let mut cursor = Cursor::new(buffer);
+        Self::write_header(&mut cursor)?;
 
         // Serialize last_seen_raids
-        let last_seen_raids_bytes = self.last_seen_raids.as_bytes();
-        (last_seen_raids_bytes.len() as u32)
+        self.last_seen_raids
             .serialize(&mut cursor)
             .map_err(|_| ProgramError::AccountDataTooSmall)?;
-        cursor
-            .write_all(last_seen_raids_bytes)
-            .map_err(|_| ProgramError::AccountDataTooSmall)?;
 
         // Serialize registered_programs_count
         self.registered_programs_count
@@ -174,149 +874,298 @@ impl ProgramStateCard {
             .serialize(&mut cursor)
             .map_err(|_| ProgramError::AccountDataTooSmall)?;
 
+        // Serialize registered_raids_count
+        self.registered_raids_count
+            .serialize(&mut cursor)
+            .map_err(|_| ProgramError::AccountDataTooSmall)?;
+
+        // Serialize registered_competitions_count
+        self.registered_competitions_count
+            .serialize(&mut cursor)
+            .map_err(|_| ProgramError::AccountDataTooSmall)?;
+
+        // Serialize bump
+        self.bump
+            .serialize(&mut cursor)
+            .map_err(|_| ProgramError::AccountDataTooSmall)?;
+
         Ok(cursor.position() as usize)
     }
 }
 
+// One page of a paginated registry. Entry `n` of a registry lives on page
+// `n / REGISTRY_PAGE_CAPACITY`, and the head counter in ProgramStateCard says how
+// many entries exist, so clients walk pages 0..=head / REGISTRY_PAGE_CAPACITY
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct ProgramRaidProgramsStateCard {
-    pub raid_program_pubkeys: String, // Comma-separated list of raid program pubkeys
+pub struct RegistryPageCard {
+    pub page_index: u64,
+    pub entries: Vec<Pubkey>, // At most REGISTRY_PAGE_CAPACITY entries, in registration order
+    pub bump: u8,             // Canonical bump of the page PDA
 }
 
-// ProgramRaidProgramsStateCard implementation
-impl ProgramRaidProgramsStateCard {
-    pub fn custom_deserialize(data: &[u8]) -> Result<(Self, usize), ProgramError> {
-        let mut cursor = Cursor::new(data);
-
-        msg!("Total data length: {} bytes", data.len());
+impl Card for RegistryPageCard {
+    const DISCRIMINATOR: [u8; 8] = *b"REGIPAGE";
+    const VERSION: u8 = 2;
+
+    // Version 2 added the bump. Pages do not record which registry they belong to,
+    // so the registry is found by matching the account address.
+    fn upgrade(
+        program_id: &Pubkey,
+        address: &Pubkey,
+        version: u8,
+        body: &[u8],
+    ) -> Result<Self, ProgramError> {
+        if version != 1 {
+            return Err(BullPosterError::UnsupportedAccountVersion.into());
+        }
+        let mut page = Self::read_card(&Self::with_current_header(body, 1))?;
+        page.bump = [
+            pda::RAID_PROGRAMS_REGISTRY_SEED,
+            pda::RAIDS_REGISTRY_SEED,
+            pda::COMPETITIONS_REGISTRY_SEED,
+        ]
+        .iter()
+        .map(|seed| pda::registry_page_address(program_id, seed, page.page_index))
+        .find(|(page_pda, _)| page_pda == address)
+        .ok_or(BullPosterError::InvalidRegistryPage)?
+        .1;
+        Ok(page)
+    }
+}
 
-        // Deserialize raid_program_pubkeys (string field)
-        let string_length =
-            u32::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
-                msg!("Failed to deserialize string length: {:?}", e);
-                ProgramError::InvalidAccountData
-            })?;
-        msg!("String length: {} bytes", string_length);
+// Every burn a user makes for one raid, accumulated in a single account
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct BurnLedger {
+    pub user_id: Pubkey,
+    pub raid_id: Pubkey, // The raid card the burns count towards
+    pub raid_program_id: Pubkey,
+    pub competition_id: Pubkey,
+    pub total_burned: u64,
+    pub burn_count: u64,
+    pub last_burn_ts: u64,
+    pub bump: u8, // Canonical bump of the burn ledger PDA
+}
 
-        cursor.set_position(cursor.position() + 4); // Move cursor past the length field
+impl Card for BurnLedger {
+    const DISCRIMINATOR: [u8; 8] = *b"BURNLDGR";
+    const VERSION: u8 = 1;
+}
 
-        let mut string_bytes = vec![0u8; string_length as usize];
-        cursor.read_exact(&mut string_bytes).map_err(|e| {
-            msg!("Failed to read string data: {:?}", e);
-            ProgramError::InvalidAccountData
-        })?;
-        let raid_program_pubkeys = String::from_utf8(string_bytes).map_err(|e| {
-            msg!("Failed to convert string data: {:?}", e);
-            ProgramError::InvalidAccountData
-        })?;
-        msg!(
-            "Deserialized `raid_program_pubkeys`: {}",
-            raid_program_pubkeys
-        );
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct RaidCard {
+    pub competition_id: Pubkey,
+    pub raid_program_id: Pubkey,
+    pub raid_id: Pubkey,
+    pub distributed_rewards: Vec<(Pubkey, u64)>, // (user, rewards) per credited user
+    pub placements: Vec<Pubkey>,                 // Users ordered by credited rewards, best first
+    pub total_burned: u64,                       // Running total of tokens burned for this raid
+    pub reward_amount: u64, // Share of the competition rewards allocated to this raid
+    pub is_slashed: bool,   // Set once the program's stake was slashed for this raid
+    pub bump: u8,           // Canonical bump of the raid card PDA
+}
 
-        let position = cursor.position();
-        msg!("Cursor position after deserialization: {} bytes", position);
-        msg!(
-            "Unused space in account: {} bytes",
-            data.len() - position as usize
-        );
+impl Card for RaidCard {
+    const DISCRIMINATOR: [u8; 8] = *b"RAIDCARD";
+    const VERSION: u8 = 2;
 
-        Ok((
-            Self {
-                raid_program_pubkeys,
-            },
-            position as usize,
-        ))
+    fn pack_into(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
+        self.custom_serialize(buffer)
     }
 
-    pub fn custom_serialize(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
-        let mut cursor = Cursor::new(buffer);
-
-        // Serialize raid_program_pubkeys
-        let raid_program_pubkeys_bytes = self.raid_program_pubkeys.as_bytes();
-        (raid_program_pubkeys_bytes.len() as u32)
-            .serialize(&mut cursor)
-            .map_err(|_| ProgramError::AccountDataTooSmall)?;
-        cursor
-            .write_all(raid_program_pubkeys_bytes)
-            .map_err(|_| ProgramError::AccountDataTooSmall)?;
-
-        Ok(cursor.position() as usize)
+    // The legacy layout kept rewards and placements as JSON and did not track
+    // burns, so legacy raids start with nothing burned. Version 2 added the bump.
+    fn upgrade(
+        program_id: &Pubkey,
+        address: &Pubkey,
+        version: u8,
+        body: &[u8],
+    ) -> Result<Self, ProgramError> {
+        match version {
+            LEGACY_VERSION => {
+                let legacy: legacy::RaidCard = legacy::read(body)?;
+                let (raid_card_pda, bump) = pda::raid_card_address(
+                    program_id,
+                    &legacy.competition_id,
+                    &legacy.raid_program_id,
+                );
+                if address != &raid_card_pda {
+                    return Err(BullPosterError::InvalidRaidCardAccount.into());
+                }
+                Ok(RaidCard {
+                    competition_id: legacy.competition_id,
+                    raid_program_id: legacy.raid_program_id,
+                    raid_id: legacy.raid_id,
+                    distributed_rewards: legacy::parse_rewards(&legacy.distributed_rewards)?,
+                    placements: legacy::parse_placements(&legacy.placements)?,
+                    total_burned: 0,
+                    reward_amount: 0,
+                    is_slashed: false,
+                    bump,
+                })
+            }
+            1 => {
+                let (mut raid_card, _) =
+                    Self::custom_deserialize(&Self::with_current_header(body, 1))?;
+                raid_card.bump = pda::raid_card_address(
+                    program_id,
+                    &raid_card.competition_id,
+                    &raid_card.raid_program_id,
+                )
+                .1;
+                Ok(raid_card)
+            }
+            _ => Err(BullPosterError::UnsupportedAccountVersion.into()),
+        }
     }
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct ProgramRaidsStateCard {
-    pub raid_pubkeys: String, // Comma-separated list of raid pubkeys
-}
+impl RaidCard {
+    // Slashed raids and raids that burned nothing have no participants to credit, so
+    // they take no share of the competition's rewards
+    pub fn earns_rewards(&self) -> bool {
+        !self.is_slashed && self.total_burned > 0
+    }
 
-impl ProgramRaidsStateCard {
     pub fn custom_deserialize(data: &[u8]) -> Result<(Self, usize), ProgramError> {
+        Self::check_header(data)?;
         let mut cursor = Cursor::new(data);
+        cursor.set_position(CARD_HEADER_LEN as u64);
+
+        let competition_id = Pubkey::deserialize(&mut &data[cursor.position() as usize..])
+            .map_err(|e| {
+                msg!("Failed to deserialize competition_id: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 32); // Pubkey is 32 bytes
 
-        // Deserialize raid_pubkeys (string field)
-        let string_length =
-            u32::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
-                msg!("Failed to deserialize string length: {:?}", e);
+        let raid_program_id = Pubkey::deserialize(&mut &data[cursor.position() as usize..])
+            .map_err(|e| {
+                msg!("Failed to deserialize raid_program_id: {:?}", e);
                 ProgramError::InvalidAccountData
             })?;
-        msg!("String length: {} bytes", string_length);
+        cursor.set_position(cursor.position() + 32);
 
-        cursor.set_position(cursor.position() + 4); // Move cursor past the length field
+        let raid_id =
+            Pubkey::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+                msg!("Failed to deserialize raid_id: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 32);
 
-        let mut string_bytes = vec![0u8; string_length as usize];
-        cursor.read_exact(&mut string_bytes).map_err(|e| {
-            msg!("Failed to read string data: {:?}", e);
+        let distributed_rewards = Vec::<(Pubkey, u64)>::deserialize(
+            &mut &data[cursor.position() as usize..],
+        )
+        .map_err(|e| {
+            msg!("Failed to deserialize distributed_rewards: {:?}", e);
             ProgramError::InvalidAccountData
         })?;
-        let raid_pubkeys = String::from_utf8(string_bytes).map_err(|e| {
-            msg!("Failed to convert string data: {:?}", e);
+        cursor.set_position(cursor.position() + 4 + 40 * distributed_rewards.len() as u64); // 4 bytes for length + 40 bytes per entry
+
+        let placements = Vec::<Pubkey>::deserialize(&mut &data[cursor.position() as usize..])
+            .map_err(|e| {
+                msg!("Failed to deserialize placements: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 4 + 32 * placements.len() as u64);
+
+        let total_burned =
+            u64::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+                msg!("Failed to deserialize total_burned: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 8); // u64 is 8 bytes
+
+        let reward_amount =
+            u64::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+                msg!("Failed to deserialize reward_amount: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 8);
+
+        let is_slashed =
+            bool::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+                msg!("Failed to deserialize is_slashed: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 1); // bool is 1 byte
+
+        let bump = u8::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+            msg!("Failed to deserialize bump: {:?}", e);
             ProgramError::InvalidAccountData
         })?;
-        msg!("Deserialized `raid_pubkeys`: {}", raid_pubkeys);
+        cursor.set_position(cursor.position() + 1);
 
         let bytes_read = cursor.position() as usize;
 
-        Ok((Self { raid_pubkeys }, bytes_read))
+        Ok((
+            Self {
+                competition_id,
+                raid_program_id,
+                raid_id,
+                distributed_rewards,
+                placements,
+                total_burned,
+                reward_amount,
+                is_slashed,
+                bump,
+            },
+            bytes_read,
+        ))
     }
 
     pub fn custom_serialize(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
         let mut cursor = Cursor::new(buffer);
+        Self::write_header(&mut cursor)?;
+
+        self.competition_id.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize competition_id: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
+
+        self.raid_program_id.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize raid_program_id: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
+
+        self.raid_id.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize raid_id: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
 
-        // Serialize raid_pubkeys
-        let raid_pubkeys_bytes = self.raid_pubkeys.as_bytes();
-        (raid_pubkeys_bytes.len() as u32)
+        self.distributed_rewards
             .serialize(&mut cursor)
-            .map_err(|_| ProgramError::AccountDataTooSmall)?;
-        cursor
-            .write_all(raid_pubkeys_bytes)
-            .map_err(|_| ProgramError::AccountDataTooSmall)?;
+            .map_err(|e| {
+                msg!("Failed to serialize distributed_rewards: {:?}", e);
+                ProgramError::AccountDataTooSmall
+            })?;
 
-        Ok(cursor.position() as usize)
-    }
-}
+        self.placements.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize placements: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct ProgramCompetitionsStateCard {
-    pub competition_pubkeys: String, // Comma-separated list of competition card pubkeys
-}
+        self.total_burned.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize total_burned: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct BurnCard {
-    pub raid_program_id: Pubkey,
-    pub competition_id: Pubkey,
-    pub user_id: Pubkey,
-    pub burn_amount: u64,
-    pub timestamp: u64,
-}
+        self.reward_amount.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize reward_amount: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct RaidCard {
-    pub competition_id: Pubkey,
-    pub raid_program_id: Pubkey,
-    pub raid_id: Pubkey,
-    pub distributed_rewards: String, // JSON string: { "user_pubkey": rewards, ... }
-    pub placements: String,          // JSON string: ["1st_user_pubkey", "2nd_user_pubkey", ...]
+        self.is_slashed.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize is_slashed: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
+
+        self.bump.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize bump: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
+
+        Ok(cursor.position() as usize)
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
@@ -326,19 +1175,82 @@ pub struct CompetitionCard {
     pub start_time: u64,
     pub end_time: u64,
     pub total_rewards_distributed: u64,
-    pub status: String,            // "awaiting", "active", "finalized", "expired"
-    pub enrolled_programs: String, // Comma-separated list of raid IDs
+    pub status: String, // "awaiting", "active", "ended", "finalized", "expired"
+    pub enrolled_programs: Vec<Pubkey>, // Raid programs that joined
     pub required_programs: u64,
     pub challenger_program_id: Option<Pubkey>,
     pub challenged_program_id: Option<Pubkey>,
     pub start_expiration: Option<u64>, // Only for PvP competitions
-    pub distributed_rewards: String,   // JSON string: { "raid_pubkey": rewards, ... }
-    pub placements: String,            // JSON string: ["1st_raid_pubkey", "2nd_raid_pubkey", ...]
+    pub distributed_rewards: Vec<(Pubkey, u64)>, // (raid card, rewards), or (merkle distributor, total)
+    pub placements: Vec<Pubkey>,                 // Raid cards ordered by tokens burned, best first
+    pub reward_cap: u64, // Most rewards that may be minted into this competition's vault
+    // Canonical bump of the competition's reward vault. The competition's own bump is
+    // not kept: its address is only derived when it is created, and the sequence in
+    // its seed is not stored, so it could not be recovered for older cards.
+    pub reward_vault_bump: u8,
+}
+
+impl Card for CompetitionCard {
+    const DISCRIMINATOR: [u8; 8] = *b"COMPCARD";
+    const VERSION: u8 = 2;
+
+    fn pack_into(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
+        self.custom_serialize(buffer)
+    }
+
+    // The legacy layout kept enrolled programs as a comma separated list and rewards
+    // and placements as JSON. Its address cannot be re-derived without the sequence,
+    // so it must name itself in competition_id, as every legacy competition did.
+    // Legacy competitions predate reward caps and get a cap of zero, which
+    // fund_reward_vault reads as nothing to mint into their vaults. Version 2 added the reward vault bump.
+    fn upgrade(
+        program_id: &Pubkey,
+        address: &Pubkey,
+        version: u8,
+        body: &[u8],
+    ) -> Result<Self, ProgramError> {
+        match version {
+            LEGACY_VERSION => {
+                let legacy: legacy::CompetitionCard = legacy::read(body)?;
+                if address != &legacy.competition_id
+                    || competition_type_index(&legacy.competition_type).is_err()
+                {
+                    return Err(BullPosterError::InvalidCompetitionAccount.into());
+                }
+                Ok(CompetitionCard {
+                    competition_id: legacy.competition_id,
+                    competition_type: legacy.competition_type,
+                    start_time: legacy.start_time,
+                    end_time: legacy.end_time,
+                    total_rewards_distributed: legacy.total_rewards_distributed,
+                    status: legacy.status,
+                    enrolled_programs: legacy::parse_pubkey_list(&legacy.enrolled_programs)?,
+                    required_programs: legacy.required_programs,
+                    challenger_program_id: legacy.challenger_program_id,
+                    challenged_program_id: legacy.challenged_program_id,
+                    start_expiration: legacy.start_expiration,
+                    distributed_rewards: legacy::parse_rewards(&legacy.distributed_rewards)?,
+                    placements: legacy::parse_placements(&legacy.placements)?,
+                    reward_cap: 0,
+                    reward_vault_bump: pda::reward_vault_address(program_id, address).1,
+                })
+            }
+            1 => {
+                let (mut competition, _) =
+                    Self::custom_deserialize(&Self::with_current_header(body, 1))?;
+                competition.reward_vault_bump = pda::reward_vault_address(program_id, address).1;
+                Ok(competition)
+            }
+            _ => Err(BullPosterError::UnsupportedAccountVersion.into()),
+        }
+    }
 }
 
 impl CompetitionCard {
     pub fn custom_deserialize(data: &[u8]) -> Result<(Self, usize), ProgramError> {
+        Self::check_header(data)?;
         let mut cursor = Cursor::new(data);
+        cursor.set_position(CARD_HEADER_LEN as u64);
 
         // Helper function for deserializing strings
         fn read_string(data: &[u8], cursor: &mut Cursor<&[u8]>) -> Result<String, ProgramError> {
@@ -361,6 +1273,18 @@ impl CompetitionCard {
             Ok(Pubkey::new_from_array(pubkey_bytes))
         }
 
+        // Helper function for deserializing Borsh vectors of fixed-size entries
+        fn read_vec<T: BorshDeserialize>(
+            data: &[u8],
+            cursor: &mut Cursor<&[u8]>,
+            entry_size: u64,
+        ) -> Result<Vec<T>, ProgramError> {
+            let entries = Vec::<T>::deserialize(&mut &data[cursor.position() as usize..])
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+            cursor.set_position(cursor.position() + 4 + entry_size * entries.len() as u64);
+            Ok(entries)
+        }
+
         let competition_id = read_pubkey(data, &mut cursor)?;
         let competition_type = read_string(data, &mut cursor)?;
         let start_time = u64::deserialize(&mut &data[cursor.position() as usize..])
@@ -373,7 +1297,7 @@ impl CompetitionCard {
             .map_err(|_| ProgramError::InvalidAccountData)?;
         cursor.set_position(cursor.position() + 8);
         let status = read_string(data, &mut cursor)?;
-        let enrolled_programs = read_string(data, &mut cursor)?;
+        let enrolled_programs = read_vec::<Pubkey>(data, &mut cursor, 32)?;
         let required_programs = u64::deserialize(&mut &data[cursor.position() as usize..])
             .map_err(|_| ProgramError::InvalidAccountData)?;
         cursor.set_position(cursor.position() + 8);
@@ -417,8 +1341,14 @@ impl CompetitionCard {
             cursor.set_position(cursor.position() + 8);
         }
 
-        let distributed_rewards = read_string(data, &mut cursor)?;
-        let placements = read_string(data, &mut cursor)?;
+        let distributed_rewards = read_vec::<(Pubkey, u64)>(data, &mut cursor, 40)?;
+        let placements = read_vec::<Pubkey>(data, &mut cursor, 32)?;
+        let reward_cap = u64::deserialize(&mut &data[cursor.position() as usize..])
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        cursor.set_position(cursor.position() + 8);
+        let reward_vault_bump = u8::deserialize(&mut &data[cursor.position() as usize..])
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        cursor.set_position(cursor.position() + 1);
 
         let bytes_read = cursor.position() as usize;
 
@@ -437,6 +1367,8 @@ impl CompetitionCard {
                 start_expiration,
                 distributed_rewards,
                 placements,
+                reward_cap,
+                reward_vault_bump,
             },
             bytes_read,
         ))
@@ -444,6 +1376,7 @@ impl CompetitionCard {
 
     pub fn custom_serialize(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
         let mut cursor = Cursor::new(buffer);
+        Self::write_header(&mut cursor)?;
 
         // Helper function for serializing strings
         fn write_string(cursor: &mut Cursor<&mut [u8]>, s: &str) -> Result<(), ProgramError> {
@@ -477,7 +1410,9 @@ impl CompetitionCard {
             .serialize(&mut cursor)
             .map_err(|_| ProgramError::AccountDataTooSmall)?;
         write_string(&mut cursor, &self.status)?;
-        write_string(&mut cursor, &self.enrolled_programs)?;
+        self.enrolled_programs
+            .serialize(&mut cursor)
+            .map_err(|_| ProgramError::AccountDataTooSmall)?;
         self.required_programs
             .serialize(&mut cursor)
             .map_err(|_| ProgramError::AccountDataTooSmall)?;
@@ -520,11 +1455,42 @@ impl CompetitionCard {
             }
         }
 
-        write_string(&mut cursor, &self.distributed_rewards)?;
-        write_string(&mut cursor, &self.placements)?;
+        self.distributed_rewards
+            .serialize(&mut cursor)
+            .map_err(|_| ProgramError::AccountDataTooSmall)?;
+        self.placements
+            .serialize(&mut cursor)
+            .map_err(|_| ProgramError::AccountDataTooSmall)?;
+        self.reward_cap
+            .serialize(&mut cursor)
+            .map_err(|_| ProgramError::AccountDataTooSmall)?;
+        self.reward_vault_bump
+            .serialize(&mut cursor)
+            .map_err(|_| ProgramError::AccountDataTooSmall)?;
 
         Ok(cursor.position() as usize)
     }
+
+    // Move the status on once its deadline has passed and report whether it changed.
+    // Competitions start as soon as their last program joins in create_raid or
+    // accept_pvp_challenge, so only the time-based moves are left to the crank.
+    pub fn advance_status(&mut self, current_time: u64) -> bool {
+        let next_status = match self.status.as_str() {
+            // PvP challenge was not accepted in time
+            "awaiting"
+                if self
+                    .start_expiration
+                    .map_or(false, |expiration| current_time > expiration) =>
+            {
+                "expired"
+            }
+            "active" if current_time >= self.end_time => "ended",
+            _ => return false,
+        };
+
+        self.status = next_status.to_string();
+        true
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
@@ -534,20 +1500,98 @@ pub struct RaidProgramCard {
     pub description: String,
     pub user_key: Pubkey,
     pub profile_picture_url: String,
-    pub pvp_requests: String, // Comma-separated list of PvP requests
-    pub raids: String,        // Comma-separated list of Raid Pubkeys
+    pub pvp_requests: Vec<Pubkey>, // PvP requests
+    pub raids: Vec<Pubkey>,        // Raid cards of every raid the program conducted
     pub is_conducting_raid: bool,
     pub active_raid_id: Pubkey, // ID of the current raid
     pub size: u64,
     pub total_rewards_distributed: u64,
     pub total_raid_wins: u64,
     pub total_raids_partaken: u64,
-    pub program_rank: u64, // New field for program's overall rank
+    pub program_rank: u64,           // New field for program's overall rank
+    pub pending_unstake_amount: u64, // Amount requested for withdrawal, 0 if none
+    pub unstake_available_at: u64,   // Time the pending unstake can be withdrawn
+    pub is_deactivated: bool,        // Set once the stake drops below the requirement
+    pub staked_amount: u64,          // Tokens held in the program's stake token account
+    pub bump: u8,                    // Canonical bump of the raid program PDA
+    pub token_account_bump: u8,      // Canonical bump of the stake token account PDA
+}
+
+impl Card for RaidProgramCard {
+    const DISCRIMINATOR: [u8; 8] = *b"RPRGCARD";
+    const VERSION: u8 = 2;
+
+    fn pack_into(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
+        self.custom_serialize(buffer)
+    }
+
+    // The legacy layout kept PvP requests and raids as comma separated lists and had
+    // no staking fields. Its stake already sits in the stake token account, and
+    // AddStake records that balance in staked_amount. Version 2 added the bumps of
+    // the raid program and its stake token account.
+    fn upgrade(
+        program_id: &Pubkey,
+        address: &Pubkey,
+        version: u8,
+        body: &[u8],
+    ) -> Result<Self, ProgramError> {
+        match version {
+            LEGACY_VERSION => {
+                let legacy: legacy::RaidProgramCard = legacy::read(body)?;
+                let (raid_program_pda, bump) =
+                    pda::raid_program_address(program_id, &legacy.user_key, &legacy.name);
+                if address != &raid_program_pda {
+                    return Err(BullPosterError::InvalidRaidProgramAccount.into());
+                }
+                Ok(RaidProgramCard {
+                    raid_program_id: legacy.raid_program_id,
+                    name: legacy.name,
+                    description: legacy.description,
+                    user_key: legacy.user_key,
+                    profile_picture_url: legacy.profile_picture_url,
+                    pvp_requests: legacy::parse_pubkey_list(&legacy.pvp_requests)?,
+                    raids: legacy::parse_pubkey_list(&legacy.raids)?,
+                    is_conducting_raid: legacy.is_conducting_raid,
+                    active_raid_id: legacy.active_raid_id,
+                    size: legacy.size,
+                    total_rewards_distributed: legacy.total_rewards_distributed,
+                    total_raid_wins: legacy.total_raid_wins,
+                    total_raids_partaken: legacy.total_raids_partaken,
+                    program_rank: legacy.program_rank,
+                    pending_unstake_amount: 0,
+                    unstake_available_at: 0,
+                    is_deactivated: false,
+                    staked_amount: 0,
+                    bump,
+                    token_account_bump: pda::raid_program_token_account_address(
+                        program_id, address,
+                    )
+                    .1,
+                })
+            }
+            1 => {
+                let (mut raid_program, _) =
+                    Self::custom_deserialize(&Self::with_current_header(body, 2))?;
+                raid_program.bump = pda::raid_program_address(
+                    program_id,
+                    &raid_program.user_key,
+                    &raid_program.name,
+                )
+                .1;
+                raid_program.token_account_bump =
+                    pda::raid_program_token_account_address(program_id, address).1;
+                Ok(raid_program)
+            }
+            _ => Err(BullPosterError::UnsupportedAccountVersion.into()),
+        }
+    }
 }
 
 impl RaidProgramCard {
     pub fn custom_deserialize(data: &[u8]) -> Result<(Self, usize), ProgramError> {
+        Self::check_header(data)?;
         let mut cursor = Cursor::new(data);
+        cursor.set_position(CARD_HEADER_LEN as u64);
 
         let raid_program_id = Pubkey::deserialize(&mut &data[cursor.position() as usize..])
             .map_err(|e| {
@@ -583,18 +1627,19 @@ impl RaidProgramCard {
             })?;
         cursor.set_position(cursor.position() + 4 + profile_picture_url.len() as u64);
 
-        let pvp_requests =
-            String::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+        let pvp_requests = Vec::<Pubkey>::deserialize(&mut &data[cursor.position() as usize..])
+            .map_err(|e| {
                 msg!("Failed to deserialize pvp_requests: {:?}", e);
                 ProgramError::InvalidAccountData
             })?;
-        cursor.set_position(cursor.position() + 4 + pvp_requests.len() as u64);
+        cursor.set_position(cursor.position() + 4 + 32 * pvp_requests.len() as u64); // 4 bytes for length + 32 bytes per Pubkey
 
-        let raids = String::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
-            msg!("Failed to deserialize raids: {:?}", e);
-            ProgramError::InvalidAccountData
-        })?;
-        cursor.set_position(cursor.position() + 4 + raids.len() as u64);
+        let raids =
+            Vec::<Pubkey>::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+                msg!("Failed to deserialize raids: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 4 + 32 * raids.len() as u64);
 
         let is_conducting_raid = bool::deserialize(&mut &data[cursor.position() as usize..])
             .map_err(|e| {
@@ -644,6 +1689,47 @@ impl RaidProgramCard {
             })?;
         cursor.set_position(cursor.position() + 8);
 
+        let pending_unstake_amount = u64::deserialize(&mut &data[cursor.position() as usize..])
+            .map_err(|e| {
+                msg!("Failed to deserialize pending_unstake_amount: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 8);
+
+        let unstake_available_at = u64::deserialize(&mut &data[cursor.position() as usize..])
+            .map_err(|e| {
+                msg!("Failed to deserialize unstake_available_at: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 8);
+
+        let is_deactivated =
+            bool::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+                msg!("Failed to deserialize is_deactivated: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 1);
+
+        let staked_amount =
+            u64::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+                msg!("Failed to deserialize staked_amount: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 8);
+
+        let bump = u8::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+            msg!("Failed to deserialize bump: {:?}", e);
+            ProgramError::InvalidAccountData
+        })?;
+        cursor.set_position(cursor.position() + 1);
+
+        let token_account_bump = u8::deserialize(&mut &data[cursor.position() as usize..])
+            .map_err(|e| {
+                msg!("Failed to deserialize token_account_bump: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 1);
+
         let bytes_read = cursor.position() as usize;
 
         Ok((
@@ -662,6 +1748,12 @@ impl RaidProgramCard {
                 total_raid_wins,
                 total_raids_partaken,
                 program_rank,
+                pending_unstake_amount,
+                unstake_available_at,
+                is_deactivated,
+                staked_amount,
+                bump,
+                token_account_bump,
             },
             bytes_read,
         ))
@@ -669,6 +1761,7 @@ impl RaidProgramCard {
 
     pub fn custom_serialize(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
         let mut cursor = Cursor::new(buffer);
+        Self::write_header(&mut cursor)?;
 
         self.raid_program_id.serialize(&mut cursor).map_err(|e| {
             msg!("Failed to serialize raid_program_id: {:?}", e);
@@ -748,6 +1841,42 @@ impl RaidProgramCard {
             ProgramError::AccountDataTooSmall
         })?;
 
+        self.pending_unstake_amount
+            .serialize(&mut cursor)
+            .map_err(|e| {
+                msg!("Failed to serialize pending_unstake_amount: {:?}", e);
+                ProgramError::AccountDataTooSmall
+            })?;
+
+        self.unstake_available_at
+            .serialize(&mut cursor)
+            .map_err(|e| {
+                msg!("Failed to serialize unstake_available_at: {:?}", e);
+                ProgramError::AccountDataTooSmall
+            })?;
+
+        self.is_deactivated.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize is_deactivated: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
+
+        self.staked_amount.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize staked_amount: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
+
+        self.bump.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize bump: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
+
+        self.token_account_bump
+            .serialize(&mut cursor)
+            .map_err(|e| {
+                msg!("Failed to serialize token_account_bump: {:?}", e);
+                ProgramError::AccountDataTooSmall
+            })?;
+
         Ok(cursor.position() as usize)
     }
 }
@@ -755,8 +1884,8 @@ impl RaidProgramCard {
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub struct UserCard {
     pub user_pubkey: Pubkey,
-    pub owned_programs: String, // Comma-separated list of owned program pubkeys
-    pub enrolled_programs: String, // Comma-separated list of enrolled program pubkeys
+    pub owned_programs: Vec<Pubkey>, // Raid programs created by the user
+    pub enrolled_programs: Vec<Pubkey>, // Raid programs the user enrolled in
     pub is_conducting_raid: bool,
     pub user_email: String,
     pub user_dob: String,
@@ -767,11 +1896,67 @@ pub struct UserCard {
     pub engagement_score: u64,
     pub streaks: u64,
     pub profile_picture_url: String,
+    pub claimable_rewards: u64, // Credited rewards not yet claimed from the claim vault
+    pub bump: u8,               // Canonical bump of the user card PDA
+}
+
+impl Card for UserCard {
+    const DISCRIMINATOR: [u8; 8] = *b"USERCARD";
+    const VERSION: u8 = 2;
+
+    fn pack_into(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
+        self.custom_serialize(buffer)
+    }
+
+    // The legacy layout kept owned and enrolled programs as comma separated lists.
+    // Version 2 added the bump.
+    fn upgrade(
+        program_id: &Pubkey,
+        address: &Pubkey,
+        version: u8,
+        body: &[u8],
+    ) -> Result<Self, ProgramError> {
+        match version {
+            LEGACY_VERSION => {
+                let legacy: legacy::UserCard = legacy::read(body)?;
+                let (user_card_pda, bump) = pda::user_card_address(program_id, &legacy.user_pubkey);
+                if address != &user_card_pda {
+                    return Err(BullPosterError::InvalidUserCardAccount.into());
+                }
+                Ok(UserCard {
+                    user_pubkey: legacy.user_pubkey,
+                    owned_programs: legacy::parse_pubkey_list(&legacy.owned_programs)?,
+                    enrolled_programs: legacy::parse_pubkey_list(&legacy.enrolled_programs)?,
+                    is_conducting_raid: legacy.is_conducting_raid,
+                    user_email: legacy.user_email,
+                    user_dob: legacy.user_dob,
+                    user_twitter_handle: legacy.user_twitter_handle,
+                    total_rewards: legacy.total_rewards,
+                    participated_raids: legacy.participated_raids,
+                    raid_ranking: legacy.raid_ranking,
+                    engagement_score: legacy.engagement_score,
+                    streaks: legacy.streaks,
+                    profile_picture_url: legacy.profile_picture_url,
+                    claimable_rewards: 0,
+                    bump,
+                })
+            }
+            1 => {
+                let (mut user_card, _) =
+                    Self::custom_deserialize(&Self::with_current_header(body, 1))?;
+                user_card.bump = pda::user_card_address(program_id, &user_card.user_pubkey).1;
+                Ok(user_card)
+            }
+            _ => Err(BullPosterError::UnsupportedAccountVersion.into()),
+        }
+    }
 }
 
 impl UserCard {
     pub fn custom_deserialize(data: &[u8]) -> Result<(Self, usize), ProgramError> {
+        Self::check_header(data)?;
         let mut cursor = Cursor::new(data);
+        cursor.set_position(CARD_HEADER_LEN as u64);
 
         let user_pubkey =
             Pubkey::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
@@ -780,19 +1965,19 @@ impl UserCard {
             })?;
         cursor.set_position(cursor.position() + 32); // Pubkey is 32 bytes
 
-        let owned_programs = String::deserialize(&mut &data[cursor.position() as usize..])
+        let owned_programs = Vec::<Pubkey>::deserialize(&mut &data[cursor.position() as usize..])
             .map_err(|e| {
-                msg!("Failed to deserialize owned_programs: {:?}", e);
-                ProgramError::InvalidAccountData
-            })?;
-        cursor.set_position(cursor.position() + 4 + owned_programs.len() as u64); // 4 bytes for length + string length
+            msg!("Failed to deserialize owned_programs: {:?}", e);
+            ProgramError::InvalidAccountData
+        })?;
+        cursor.set_position(cursor.position() + 4 + 32 * owned_programs.len() as u64); // 4 bytes for length + 32 bytes per Pubkey
 
-        let enrolled_programs = String::deserialize(&mut &data[cursor.position() as usize..])
-            .map_err(|e| {
+        let enrolled_programs =
+            Vec::<Pubkey>::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
                 msg!("Failed to deserialize enrolled_programs: {:?}", e);
                 ProgramError::InvalidAccountData
             })?;
-        cursor.set_position(cursor.position() + 4 + enrolled_programs.len() as u64);
+        cursor.set_position(cursor.position() + 4 + 32 * enrolled_programs.len() as u64);
 
         let is_conducting_raid = bool::deserialize(&mut &data[cursor.position() as usize..])
             .map_err(|e| {
@@ -863,6 +2048,19 @@ impl UserCard {
             })?;
         cursor.set_position(cursor.position() + 4 + profile_picture_url.len() as u64);
 
+        let claimable_rewards = u64::deserialize(&mut &data[cursor.position() as usize..])
+            .map_err(|e| {
+                msg!("Failed to deserialize claimable_rewards: {:?}", e);
+                ProgramError::InvalidAccountData
+            })?;
+        cursor.set_position(cursor.position() + 8);
+
+        let bump = u8::deserialize(&mut &data[cursor.position() as usize..]).map_err(|e| {
+            msg!("Failed to deserialize bump: {:?}", e);
+            ProgramError::InvalidAccountData
+        })?;
+        cursor.set_position(cursor.position() + 1);
+
         let bytes_read = cursor.position() as usize;
 
         Ok((
@@ -880,6 +2078,8 @@ impl UserCard {
                 engagement_score,
                 streaks,
                 profile_picture_url,
+                claimable_rewards,
+                bump,
             },
             bytes_read,
         ))
@@ -887,6 +2087,7 @@ impl UserCard {
 
     pub fn custom_serialize(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
         let mut cursor = Cursor::new(buffer);
+        Self::write_header(&mut cursor)?;
 
         self.user_pubkey.serialize(&mut cursor).map_err(|e| {
             msg!("Failed to serialize user_pubkey: {:?}", e);
@@ -961,10 +2162,62 @@ impl UserCard {
                 ProgramError::AccountDataTooSmall
             })?;
 
+        self.claimable_rewards.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize claimable_rewards: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
+
+        self.bump.serialize(&mut cursor).map_err(|e| {
+            msg!("Failed to serialize bump: {:?}", e);
+            ProgramError::AccountDataTooSmall
+        })?;
+
         Ok(cursor.position() as usize)
     }
 }
 
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct MerkleDistributorCard {
+    pub competition_id: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,  // Sum of all leaf amounts
+    pub total_claimed: u64, // Amount paid out through proofs so far
+    pub leaf_count: u64,
+    pub claimed_bitmap: Vec<u8>, // One bit per leaf index, set once claimed
+    pub bump: u8,                // Canonical bump of the distributor PDA
+}
+
+impl Card for MerkleDistributorCard {
+    const DISCRIMINATOR: [u8; 8] = *b"MRKLDIST";
+    const VERSION: u8 = 2;
+
+    // Version 2 added the bump
+    fn upgrade(
+        program_id: &Pubkey,
+        _address: &Pubkey,
+        version: u8,
+        body: &[u8],
+    ) -> Result<Self, ProgramError> {
+        if version != 1 {
+            return Err(BullPosterError::UnsupportedAccountVersion.into());
+        }
+        let mut distributor = Self::read_card(&Self::with_current_header(body, 1))?;
+        distributor.bump =
+            pda::merkle_distributor_address(program_id, &distributor.competition_id).1;
+        Ok(distributor)
+    }
+}
+
+impl MerkleDistributorCard {
+    // Everything but the claimed bitmap's bytes: header, competition, root, three u64s,
+    // the bitmap's length prefix and the bump
+    const FIXED_LEN: usize = CARD_HEADER_LEN + 32 + 32 + 8 + 8 + 8 + 4 + 1;
+
+    // The distributor is created through a CPI, which allocates at most
+    // MAX_PERMITTED_DATA_INCREASE bytes, so its bitmap caps the number of leaves
+    pub const MAX_LEAF_COUNT: u64 = ((MAX_PERMITTED_DATA_INCREASE - Self::FIXED_LEN) * 8) as u64;
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub struct RaidHistory {
     pub history: String, // JSON string containing raid history
@@ -973,11 +2226,60 @@ pub struct RaidHistory {
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub struct ProgramLeaderboardStateCard {
     pub leaderboard_data: String, // JSON string containing leaderboard data for all competition types
+    pub bump: u8,                 // Canonical bump of the leaderboard PDA
+}
+
+impl Card for ProgramLeaderboardStateCard {
+    const DISCRIMINATOR: [u8; 8] = *b"LDRBOARD";
+    const VERSION: u8 = 2;
+
+    // The legacy layout is the same JSON string without the bump. Version 2 added
+    // the bump.
+    fn upgrade(
+        program_id: &Pubkey,
+        address: &Pubkey,
+        version: u8,
+        body: &[u8],
+    ) -> Result<Self, ProgramError> {
+        match version {
+            LEGACY_VERSION => {
+                let (leaderboard_pda, bump) = pda::leaderboard_address(program_id);
+                if address != &leaderboard_pda {
+                    return Err(BullPosterError::InvalidLeaderboardAccount.into());
+                }
+                let legacy: legacy::ProgramLeaderboardStateCard = legacy::read(body)?;
+                Ok(ProgramLeaderboardStateCard {
+                    leaderboard_data: legacy.leaderboard_data,
+                    bump,
+                })
+            }
+            1 => {
+                let mut leaderboard = Self::read_card(&Self::with_current_header(body, 1))?;
+                leaderboard.bump = pda::leaderboard_address(program_id).1;
+                Ok(leaderboard)
+            }
+            _ => Err(BullPosterError::UnsupportedAccountVersion.into()),
+        }
+    }
 }
 
 // Program entrypoint
 #[cfg(not(feature = "no-entrypoint"))]
-entrypoint!(process_instruction);
+entrypoint!(program_entrypoint);
+
+// Logs the message behind a custom error code before handing the error back to the runtime
+#[cfg(not(feature = "no-entrypoint"))]
+fn program_entrypoint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    if let Err(error) = process_instruction(program_id, accounts, instruction_data) {
+        error.print::<BullPosterError>();
+        return Err(error);
+    }
+    Ok(())
+}
 
 pub fn process_instruction(
     program_id: &Pubkey,
@@ -988,24 +2290,22 @@ pub fn process_instruction(
     msg!("Instruction data length: {}", instruction_data.len());
     msg!("Instruction data: {:?}", instruction_data);
 
-    if instruction_data.is_empty() {
-        return Err(ProgramError::InvalidInstructionData);
-    }
+    let instruction = BullPosterInstruction::unpack(instruction_data)?;
 
-    let instruction_type = instruction_data[0];
-    let instruction_body = &instruction_data[1..];
+    // The Borsh variant index leads the data and is what the pause bitmask refers to
+    check_not_paused(program_id, accounts, instruction_data[0])?;
 
-    match instruction_type {
-        1 => {
+    match instruction {
+        BullPosterInstruction::InitializeProgram { initial_authority } => {
             msg!("Instruction: Initialize Program");
-            initialize_program(program_id, accounts)
+            initialize_program(program_id, accounts, initial_authority)
         }
-        2 => {
+        BullPosterInstruction::CreateRaidProgram {
+            name,
+            description,
+            profile_picture_url,
+        } => {
             msg!("Instruction: Create BullPoster Program");
-            let (name, rest) = unpack_string(instruction_body)?;
-            let (description, rest) = unpack_string(rest)?;
-            let (profile_picture_url, _) = unpack_string(rest)?;
-
             msg!("Name: {}", name);
             msg!("Description: {}", description);
             msg!("Profile Picture URL: {}", profile_picture_url);
@@ -1016,48 +2316,39 @@ pub fn process_instruction(
                 &name,
                 description,
                 profile_picture_url,
-                REQUIRED_STAKE_AMOUNT,
             )
         }
-        3 => {
+        BullPosterInstruction::AuthorityMint { amount } => {
             msg!("Instruction: Authority Transfer");
-            if instruction_body.len() != 8 {
-                return Err(ProgramError::InvalidInstructionData);
-            }
-            let amount = u64::from_le_bytes(instruction_body.try_into().unwrap());
             authority_mint(program_id, accounts, amount)
         }
-        4 => {
+        BullPosterInstruction::CreateRaid { competition_type } => {
             msg!("Instruction: Create Raid");
-            let (competition_type, _) = unpack_string(instruction_body)?;
             create_raid(program_id, accounts, competition_type)
         }
-        5 => {
+        BullPosterInstruction::AcceptPVPRequest => {
             msg!("Instruction: Accept PVP Request");
-            accept_pvp_challenge(accounts)
+            accept_pvp_challenge(program_id, accounts)
         }
-        6 => {
+        BullPosterInstruction::BurnTokens { burn_amount } => {
             msg!("Instruction: Burn Tokens");
-            if instruction_body.len() != 8 {
-                return Err(ProgramError::InvalidInstructionData);
-            }
-            let burn_amount = u64::from_le_bytes(instruction_body.try_into().unwrap());
             burn_tokens_for_raid(program_id, accounts, burn_amount)
         }
-        7 => {
+        BullPosterInstruction::EnrollInProgram => {
             msg!("Instruction: Enroll In Program");
             enroll_in_program(program_id, accounts)
         }
-        8 => {
+        BullPosterInstruction::CreateUserCard => {
             msg!("Instruction: Create User Card");
             create_user_card(program_id, accounts)
         }
-        9 => {
+        BullPosterInstruction::UpdateUserCard {
+            user_email,
+            user_twitter_handle,
+            user_dob,
+            profile_picture_url,
+        } => {
             msg!("Instruction: Update User Card");
-            let (user_email, rest) = unpack_string(instruction_body)?;
-            let (user_twitter_handle, rest) = unpack_string(rest)?;
-            let (user_dob, rest) = unpack_string(rest)?;
-            let (profile_picture_url, _) = unpack_string(rest)?;
             update_user_card(
                 program_id,
                 accounts,
@@ -1067,51 +2358,189 @@ pub fn process_instruction(
                 profile_picture_url,
             )
         }
-        _ => Err(ProgramError::InvalidInstructionData),
+        BullPosterInstruction::FinalizeCompetition => {
+            msg!("Instruction: Finalize Competition");
+            finalize_competition(program_id, accounts)
+        }
+        BullPosterInstruction::CheckRaidStatus => {
+            msg!("Instruction: Check Raid Status");
+            check_raid_status(program_id, accounts)
+        }
+        BullPosterInstruction::FundRewardVault { amount, mint } => {
+            msg!("Instruction: Fund Reward Vault");
+            fund_reward_vault(program_id, accounts, amount, mint)
+        }
+        BullPosterInstruction::DistributeRewards => {
+            msg!("Instruction: Distribute Rewards");
+            distribute_rewards(program_id, accounts)
+        }
+        BullPosterInstruction::CreditRaidRewards { engagement_scores } => {
+            msg!("Instruction: Credit Raid Rewards");
+            credit_raid_rewards(program_id, accounts, engagement_scores)
+        }
+        BullPosterInstruction::ClaimRewards => {
+            msg!("Instruction: Claim Rewards");
+            claim_rewards(program_id, accounts)
+        }
+        BullPosterInstruction::PostMerkleRoot {
+            merkle_root,
+            total_amount,
+            leaf_count,
+        } => {
+            msg!("Instruction: Post Merkle Root");
+            post_merkle_root(program_id, accounts, merkle_root, total_amount, leaf_count)
+        }
+        BullPosterInstruction::ClaimWithProof {
+            index,
+            amount,
+            proof,
+        } => {
+            msg!("Instruction: Claim With Proof");
+            claim_with_proof(program_id, accounts, index, amount, proof)
+        }
+        BullPosterInstruction::RequestUnstake { amount } => {
+            msg!("Instruction: Request Unstake");
+            request_unstake(program_id, accounts, amount)
+        }
+        BullPosterInstruction::WithdrawStake => {
+            msg!("Instruction: Withdraw Stake");
+            withdraw_stake(program_id, accounts)
+        }
+        BullPosterInstruction::AddStake { amount } => {
+            msg!("Instruction: Add Stake");
+            add_stake(program_id, accounts, amount)
+        }
+        BullPosterInstruction::UpdateConfig {
+            required_stake_amount,
+            silver_stake_amount,
+            gold_stake_amount,
+            unstake_cooldown,
+            slash_basis_points,
+            raid_start_delay,
+            raid_duration,
+            pvp_acceptance_window,
+            reward_caps,
+            max_supply,
+            epoch_emission_budget,
+            epoch_length,
+            emission_threshold,
+        } => {
+            msg!("Instruction: Update Config");
+            update_config(
+                program_id,
+                accounts,
+                GlobalConfig {
+                    required_stake_amount,
+                    silver_stake_amount,
+                    gold_stake_amount,
+                    unstake_cooldown,
+                    slash_basis_points,
+                    raid_start_delay,
+                    raid_duration,
+                    pvp_acceptance_window,
+                    reward_caps,
+                    max_supply,
+                    epoch_emission_budget,
+                    epoch_length,
+                    emission_threshold,
+                    ..GlobalConfig::default() // Authority, pause state and mint totals are kept
+                },
+            )
+        }
+        BullPosterInstruction::ProposeAuthority { new_authority } => {
+            msg!("Instruction: Propose Authority");
+            propose_authority(program_id, accounts, new_authority)
+        }
+        BullPosterInstruction::AcceptAuthority => {
+            msg!("Instruction: Accept Authority");
+            accept_authority(program_id, accounts)
+        }
+        BullPosterInstruction::SetPause {
+            paused,
+            paused_instructions,
+        } => {
+            msg!("Instruction: Set Pause");
+            set_pause(program_id, accounts, paused, paused_instructions)
+        }
+        BullPosterInstruction::ResizeAccount { new_len } => {
+            msg!("Instruction: Resize Account");
+            resize_account(program_id, accounts, new_len as usize)
+        }
+        BullPosterInstruction::MigrateAccount => {
+            msg!("Instruction: Migrate Account");
+            migrate_account(program_id, accounts)
+        }
+        BullPosterInstruction::InitializeConfig { initial_authority } => {
+            msg!("Instruction: Initialize Config");
+            initialize_config(program_id, accounts, initial_authority)
+        }
+        BullPosterInstruction::LeaveCompetition => {
+            msg!("Instruction: Leave Competition");
+            leave_competition(program_id, accounts)
+        }
     }
 }
 
-fn unpack_string(input: &[u8]) -> Result<(String, &[u8]), ProgramError> {
-    if input.len() < 4 {
-        return Err(ProgramError::InvalidInstructionData);
+// Stop paused instructions before they touch any state. Initialization, claims,
+// status checks, migrations and the admin instructions stay open; every other
+// instruction must pass the config account somewhere in its account list.
+fn check_not_paused(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_type: u8,
+) -> ProgramResult {
+    if matches!(instruction_type, 0 | 10 | 14 | 16 | 20..=23 | 25 | 26) {
+        return Ok(());
     }
-    let length = u32::from_le_bytes(input[..4].try_into().unwrap()) as usize;
-    let start = 4;
-    let end = start + length;
 
-    if input.len() < end {
-        return Err(ProgramError::InvalidInstructionData);
-    }
+    // Spot the config by its discriminator; load then checks it against the stored bump
+    let config_account = accounts
+        .iter()
+        .find(|account| {
+            account.owner == program_id
+                && account
+                    .data
+                    .borrow()
+                    .starts_with(&GlobalConfig::DISCRIMINATOR)
+        })
+        .ok_or_else(|| {
+            msg!("Global config account is required");
+            ProgramError::NotEnoughAccountKeys
+        })?;
+    let config = GlobalConfig::load(program_id, config_account)?;
 
-    let string_bytes = &input[start..end];
-    let string = String::from_utf8(string_bytes.to_vec())
-        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    let instruction_bit = 1u64.checked_shl(instruction_type as u32).unwrap_or(0);
+    if config.paused || config.paused_instructions & instruction_bit != 0 {
+        msg!("Instruction {} is paused", instruction_type);
+        return Err(BullPosterError::ProgramPaused.into());
+    }
 
-    Ok((string, &input[end..]))
+    Ok(())
 }
 
-fn initialize_program(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+fn initialize_program(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    initial_authority: Option<Pubkey>,
+) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let initializer = next_account_info(account_info_iter)?;
-    let token_mint_account = next_account_info(account_info_iter)?;
-    let state_account = next_account_info(account_info_iter)?;
-    let program_raid_programs_state_account = next_account_info(account_info_iter)?;
-    let program_raids_state_account = next_account_info(account_info_iter)?;
-    let program_competitions_state_account = next_account_info(account_info_iter)?;
-    let leaderboard_account = next_account_info(account_info_iter)?;
-    let system_program = next_account_info(account_info_iter)?;
-    let token_program = next_account_info(account_info_iter)?;
-    let rent = next_account_info(account_info_iter)?;
+    let initializer = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let token_mint_account = next_account_info(account_info_iter)?.writable()?;
+    let state_account = next_account_info(account_info_iter)?.writable()?;
+    let leaderboard_account = next_account_info(account_info_iter)?.writable()?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+    let token_program = next_account_info(account_info_iter)?.token_program()?;
+    let rent = next_account_info(account_info_iter)?.rent_sysvar()?;
+    let config_account = next_account_info(account_info_iter)?.writable()?;
 
     if initializer.key != &AUTHORITY_PUBKEY {
-        return Err(ProgramError::InvalidAccountData.into());
+        return Err(BullPosterError::Unauthorized.into());
     }
 
     // Derive PDA for token mint
-    let (token_mint_pda, mint_bump) =
-        Pubkey::find_program_address(&[b"pda_token_mint"], program_id);
+    let (token_mint_pda, mint_bump) = pda::token_mint_address(program_id);
     if token_mint_account.key != &token_mint_pda {
-        return Err(ProgramError::InvalidAccountData.into());
+        return Err(BullPosterError::InvalidTokenMint.into());
     }
 
     // Create token mint account as a PDA
@@ -1122,7 +2551,7 @@ fn initialize_program(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramR
         token_program.key,
         system_program,
         token_mint_account,
-        &[b"pda_token_mint", &[mint_bump]],
+        &[pda::TOKEN_MINT_SEED, &[mint_bump]],
     )?;
 
     // Initialize mint
@@ -1135,21 +2564,23 @@ fn initialize_program(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramR
             9,
         )?,
         &[token_mint_account.clone(), rent.clone()],
-        &[&[b"pda_token_mint", &[mint_bump]]],
+        &[&[pda::TOKEN_MINT_SEED, &[mint_bump]]],
     )?;
 
     // Derive PDA for program state
-    let (state_account_pda, state_bump) =
-        Pubkey::find_program_address(&[b"program_state"], program_id);
+    let (state_account_pda, state_bump) = pda::program_state_address(program_id);
     if state_account.key != &state_account_pda {
-        return Err(ProgramError::InvalidAccountData.into());
+        return Err(BullPosterError::InvalidProgramStateAccount.into());
     }
 
     // Calculate space needed for state account
     let state_data = ProgramStateCard {
-        last_seen_raids: "{}".to_string(),
+        last_seen_raids: [LastSeenRaid::default(); 5],
         registered_programs_count: 0,
         registered_users_count: 0,
+        registered_raids_count: 0,
+        registered_competitions_count: 0,
+        bump: state_bump,
     };
 
     let space = 1000;
@@ -1162,127 +2593,119 @@ fn initialize_program(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramR
         program_id,
         system_program,
         state_account,
-        &[b"program_state", &[state_bump]],
+        &[pda::PROGRAM_STATE_SEED, &[state_bump]],
     )?;
 
     // Initialize ProgramStateCard in the state account
-    state_data.serialize(&mut &mut state_account.data.borrow_mut()[..])?;
+    state_data.custom_serialize(&mut state_account.data.borrow_mut())?;
 
-    // Derive PDA for program raids state
-    let (raid_programs_state_account_pda, state_bump) =
-        Pubkey::find_program_address(&[b"program_raid_programs_state"], program_id);
-    if program_raid_programs_state_account.key != &raid_programs_state_account_pda {
-        return Err(ProgramError::InvalidAccountData.into());
+    // Derive PDA for program leaderboard state
+    let (leaderboard_state_account_pda, state_bump) = pda::leaderboard_address(program_id);
+    if leaderboard_account.key != &leaderboard_state_account_pda {
+        return Err(BullPosterError::InvalidLeaderboardAccount.into());
     }
 
-    // Calculate space needed for program raids state account
-    let raid_programs_state_data = ProgramRaidProgramsStateCard {
-        raid_program_pubkeys: String::new(),
+    // Calculate space needed for program leaderboard state account
+    let leaderboard_state_data = ProgramLeaderboardStateCard {
+        leaderboard_data: "{}".to_string(),
+        bump: state_bump,
     };
 
-    let raids_space = 1000;
+    let leaderboard_space = 1000;
 
-    // Create program raids state account
+    // Create program leaderboard account
     create_pda_account(
         initializer,
         &Rent::get()?,
-        raids_space,
+        leaderboard_space,
         program_id,
         system_program,
-        program_raid_programs_state_account,
-        &[b"program_raid_programs_state", &[state_bump]],
+        leaderboard_account,
+        &[pda::LEADERBOARD_SEED, &[state_bump]],
     )?;
 
     // Initialize ProgramStateCard in the state account
-    raid_programs_state_data
-        .serialize(&mut &mut program_raid_programs_state_account.data.borrow_mut()[..])?;
+    leaderboard_state_data.write_card(&mut leaderboard_account.data.borrow_mut())?;
 
-    // Derive PDA for program raids state
-    let (raids_state_account_pda, state_bump) =
-        Pubkey::find_program_address(&[b"program_raids_state"], program_id);
-    if program_raids_state_account.key != &raids_state_account_pda {
-        return Err(ProgramError::InvalidAccountData.into());
+    // Derive PDA for global config
+    let (config_pda, config_bump) = pda::config_address(program_id);
+    if config_account.key != &config_pda {
+        return Err(BullPosterError::InvalidConfigAccount.into());
     }
 
-    // Calculate space needed for program raids state account
-    let raids_state_data = ProgramRaidsStateCard {
-        raid_pubkeys: String::new(),
-    };
+    let config_data = GlobalConfig::with_defaults(
+        program_id,
+        initial_authority.unwrap_or(*initializer.key),
+        Clock::get()?.unix_timestamp as u64,
+    );
 
-    let raids_space = 1000;
+    let config_space = 1000;
 
-    // Create program raids state account
+    // Create global config account
     create_pda_account(
         initializer,
         &Rent::get()?,
-        raids_space,
+        config_space,
         program_id,
         system_program,
-        program_raids_state_account,
-        &[b"program_raids_state", &[state_bump]],
+        config_account,
+        &[pda::CONFIG_SEED, &[config_bump]],
     )?;
 
-    // Initialize ProgramStateCard in the state account
-    raids_state_data.serialize(&mut &mut program_raids_state_account.data.borrow_mut()[..])?;
+    config_data.write_card(&mut config_account.data.borrow_mut())?;
 
-    // Derive PDA for program competitions state
-    let (competitions_state_account_pda, state_bump) =
-        Pubkey::find_program_address(&[b"program_competitions_state"], program_id);
-    if program_competitions_state_account.key != &competitions_state_account_pda {
-        return Err(ProgramError::InvalidAccountData.into());
-    }
-
-    // Calculate space needed for program competitions state account
-    let competitions_state_data = ProgramCompetitionsStateCard {
-        competition_pubkeys: String::new(),
-    };
-
-    let competitions_space = 1000;
-
-    // Create program competitions account
-    create_pda_account(
-        initializer,
-        &Rent::get()?,
-        competitions_space,
-        program_id,
-        system_program,
-        program_competitions_state_account,
-        &[b"program_competitions_state", &[state_bump]],
-    )?;
+    msg!("Token mint and program state initialized");
+    Ok(())
+}
 
-    // Initialize ProgramStateCard in the state account
-    competitions_state_data
-        .serialize(&mut &mut program_competitions_state_account.data.borrow_mut()[..])?;
+// Create the global config for a deployment whose InitializeProgram ran before
+// GlobalConfig existed. Tokens minted since then count towards the supply cap.
+fn initialize_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    initial_authority: Option<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let initializer = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let (config_pda, config_bump) = pda::config_address(program_id);
+    let config_account = next_account_info(account_info_iter)?
+        .writable()?
+        .address(&config_pda, BullPosterError::InvalidConfigAccount)?
+        .uninitialized(BullPosterError::ConfigAlreadyExists)?;
+    let token_mint_account = next_account_info(account_info_iter)?
+        .token_mint(program_id, pda::token_mint_address(program_id).1)?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
 
-    // Derive PDA for program leaderboard state
-    let (leaderboard_state_account_pda, state_bump) =
-        Pubkey::find_program_address(&[b"program_leaderboard_state"], program_id);
-    if leaderboard_account.key != &leaderboard_state_account_pda {
-        return Err(ProgramError::InvalidAccountData.into());
+    if initializer.key != &AUTHORITY_PUBKEY {
+        return Err(BullPosterError::Unauthorized.into());
     }
 
-    // Calculate space needed for program leaderboard state account
-    let leaderboard_state_data = ProgramLeaderboardStateCard {
-        leaderboard_data: "{}".to_string(),
-    };
-
-    let leaderboard_space = 1000;
+    let mut config_data = GlobalConfig::with_defaults(
+        program_id,
+        initial_authority.unwrap_or(*initializer.key),
+        Clock::get()?.unix_timestamp as u64,
+    );
+    config_data.total_minted =
+        StateWithExtensions::<Mint>::unpack(&token_mint_account.data.borrow())?
+            .base
+            .supply;
 
-    // Create program leaderboard account
     create_pda_account(
         initializer,
         &Rent::get()?,
-        leaderboard_space,
+        1000,
         program_id,
         system_program,
-        leaderboard_account,
-        &[b"program_leaderboard_state", &[state_bump]],
+        config_account,
+        &[pda::CONFIG_SEED, &[config_bump]],
     )?;
 
-    // Initialize ProgramStateCard in the state account
-    leaderboard_state_data.serialize(&mut &mut leaderboard_account.data.borrow_mut()[..])?;
+    config_data.write_card(&mut config_account.data.borrow_mut())?;
 
-    msg!("Token mint and program state initialized");
+    msg!(
+        "Global config initialized, {} tokens already minted",
+        config_data.total_minted
+    );
     Ok(())
 }
 
@@ -1292,14 +2715,13 @@ pub fn initialize_raid_program(
     program_name: &str,
     description: String,
     profile_picture_url: String,
-    stake_amount: u64,
 ) -> ProgramResult {
     msg!("Entering initialize_raid_program");
 
     // Input validation
     if program_name.len() > 32 || description.len() > 256 || profile_picture_url.len() > 128 {
         msg!("Error: Input strings exceed maximum allowed length");
-        return Err(ProgramError::InvalidArgument);
+        return Err(BullPosterError::InputTooLong.into());
     }
 
     msg!(
@@ -1309,77 +2731,84 @@ pub fn initialize_raid_program(
     );
     msg!("Description length: {}", description.len());
     msg!("Profile picture URL length: {}", profile_picture_url.len());
-    msg!("Stake amount: {}", stake_amount);
 
     let account_info_iter = &mut accounts.iter();
-    let user_account = next_account_info(account_info_iter)?;
-    let user_token_account = next_account_info(account_info_iter)?;
-    let raid_program_data_account = next_account_info(account_info_iter)?;
-    let raid_program_token_account = next_account_info(account_info_iter)?;
-    let program_state_account = next_account_info(account_info_iter)?;
-    let program_raid_programs_state_account = next_account_info(account_info_iter)?;
-    let user_card_account = next_account_info(account_info_iter)?;
+    let user_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let user_token_account = next_account_info(account_info_iter)?.writable()?;
+    let raid_program_data_account = next_account_info(account_info_iter)?.writable()?;
+    let raid_program_token_account = next_account_info(account_info_iter)?.writable()?;
+    let program_state_account = next_account_info(account_info_iter)?
+        .card::<ProgramStateCard>(program_id)?
+        .writable()?;
+    let raid_programs_page_account = next_account_info(account_info_iter)?.writable()?;
+    let user_card_account = next_account_info(account_info_iter)?
+        .card::<UserCard>(program_id)?
+        .writable()?;
     let token_mint_account = next_account_info(account_info_iter)?;
-    let token_program = next_account_info(account_info_iter)?;
-    let system_program = next_account_info(account_info_iter)?;
-    let rent = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?.token_program()?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+    let rent = next_account_info(account_info_iter)?.rent_sysvar()?;
+    let config_account = next_account_info(account_info_iter)?;
 
-    // Verify user is signer
-    if !user_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    let config = GlobalConfig::load(program_id, config_account)?;
+    let stake_amount = config.required_stake_amount;
+    msg!("Stake amount: {}", stake_amount);
 
-    // Verify program state account
-    let (program_state_pda, _) = Pubkey::find_program_address(&[b"program_state"], program_id);
-    if program_state_account.key != &program_state_pda {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    // Verify the token mint
+    token_mint_account.token_mint(program_id, config.token_mint_bump)?;
 
-    // Verify program raid programs state account
-    let (program_raid_programs_state_pda, _) =
-        Pubkey::find_program_address(&[b"program_raid_programs_state"], program_id);
-    if program_raid_programs_state_account.key != &program_raid_programs_state_pda {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    // Deserialize ProgramStateCard from account data using custom_deserialize
+    let (mut program_state, bytes_read) = ProgramStateCard::custom_deserialize(
+        &program_state_account.data.borrow(),
+    )
+    .map_err(|e| {
+        msg!("Failed to deserialize ProgramStateCard: {:?}", e);
+        ProgramError::InvalidAccountData
+    })?;
 
-    // Derive PDA for user card account
-    let user_card_seed = format!("user_card_{}", user_account.key.to_string());
-    let mut hasher = Sha256::new();
-    hasher.update(user_card_seed.as_bytes());
-    let result = hasher.finalize();
-    let seed = &result[..32];
+    // Verify program state account with its stored bump
+    let program_state_pda =
+        pda::create_address(program_id, &[pda::PROGRAM_STATE_SEED], program_state.bump)?;
+    if program_state_account.key != &program_state_pda {
+        return Err(BullPosterError::InvalidProgramStateAccount.into());
+    }
 
-    let (user_card_pda, bump) = Pubkey::find_program_address(&[seed], program_id);
+    let (mut user_card, user_card_bytes_read) =
+        UserCard::custom_deserialize(&user_card_account.data.borrow()).map_err(|e| {
+            msg!("Failed to deserialize UserCard: {:?}", e);
+            ProgramError::InvalidAccountData
+        })?;
 
+    // Verify user card account with its stored bump
+    let user_card_pda = pda::create_address(
+        program_id,
+        &[&pda::user_card_seed(user_account.key)],
+        user_card.bump,
+    )?;
     if user_card_account.key != &user_card_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(BullPosterError::InvalidUserCardAccount.into());
     }
 
-    // Generate the seed using SHA-256 and take the first 32 bytes
-    let seed_string = format!(
-        "raid_program_{}_{}",
-        user_account.key.to_string(),
-        program_name
-    );
-    let mut hasher = Sha256::new();
-    hasher.update(seed_string.as_bytes());
-    let result = hasher.finalize();
-    let seed = &result[..32];
-
-    // Use the hash as the seed
-    let (raid_program_data_account_pda, bump) = Pubkey::find_program_address(&[&seed], program_id);
+    // Derive PDA for the raid program account
+    let raid_program_seed = pda::raid_program_seed(user_account.key, program_name);
+    let (raid_program_data_account_pda, bump) =
+        pda::raid_program_address(program_id, user_account.key, program_name);
 
     // Verify provided Raid Program Account matches derived PDA
     if raid_program_data_account.key != &raid_program_data_account_pda {
         msg!("Error: Raid Program Account does not match derived PDA");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(BullPosterError::InvalidRaidProgramAccount.into());
     }
 
+    // Derive PDA for the raid program's stake token account
+    let (raid_program_token_account_pda, token_account_bump) =
+        pda::raid_program_token_account_address(program_id, raid_program_data_account.key);
+
     // Check if account already exists
     if !raid_program_data_account.data_is_empty() {
         msg!("Raid Program Account is not empty");
         // verify account data is of the correct type
-        return Err(ProgramError::InvalidAccountData);
+        return Err(BullPosterError::RaidProgramAlreadyExists.into());
     } else {
         // Account doesn't exist, create and initialize it
         // Create program account
@@ -1389,8 +2818,8 @@ pub fn initialize_raid_program(
             description: description.clone(),
             user_key: *user_account.key,
             profile_picture_url: profile_picture_url.clone(),
-            pvp_requests: String::new(),
-            raids: String::new(),
+            pvp_requests: Vec::new(),
+            raids: Vec::new(),
             is_conducting_raid: false,
             active_raid_id: Pubkey::default(),
             size: 0,
@@ -1398,6 +2827,12 @@ pub fn initialize_raid_program(
             total_raid_wins: 0,
             total_raids_partaken: 0,
             program_rank: 0,
+            pending_unstake_amount: 0,
+            unstake_available_at: 0,
+            is_deactivated: false,
+            staked_amount: stake_amount,
+            bump,
+            token_account_bump,
         };
 
         let space = 1000;
@@ -1407,31 +2842,20 @@ pub fn initialize_raid_program(
 
         msg!("Creating Raid Program PDA account...");
         create_pda_account(
-            user_account,              // The user initiating the transaction
-            &Rent::get()?,             // Required lamports for rent-exemption
-            space,                     // Size of the account to be created
-            program_id,                // Token program associated with this action
-            system_program,            // System program for creating the account
-            raid_program_data_account, // The PDA account for the raid program
-            &[&result, &[bump]],       // Seeds for generating PDA and the bump seed
+            user_account,                   // The user initiating the transaction
+            &Rent::get()?,                  // Required lamports for rent-exemption
+            space,                          // Size of the account to be created
+            program_id,                     // Token program associated with this action
+            system_program,                 // System program for creating the account
+            raid_program_data_account,      // The PDA account for the raid program
+            &[&raid_program_seed, &[bump]], // Seeds for generating PDA and the bump seed
         )?;
 
         msg!("Serializing RaidProgramCard to Raid Program account...");
-        raid_program_space.serialize(&mut &mut raid_program_data_account.data.borrow_mut()[..])?;
-
-        // Generate the seed using SHA-256 and take the first 32 bytes
-        let raid_program_token_account_seed_string = format!(
-            "raid_program_token_account_{}",
-            raid_program_data_account.key.to_string()
-        );
-        let mut hasher = Sha256::new();
-        hasher.update(raid_program_token_account_seed_string.as_bytes());
-        let result = hasher.finalize();
-        let raid_program_token_account_seed = &result[..32];
+        raid_program_space.custom_serialize(&mut raid_program_data_account.data.borrow_mut())?;
 
-        // Derive PDA for the token account
-        let (raid_program_token_account_pda, token_account_bump) =
-            Pubkey::find_program_address(&[raid_program_token_account_seed], program_id);
+        let raid_program_token_account_seed =
+            pda::raid_program_token_account_seed(raid_program_data_account.key);
 
         msg!("Creating Raid Program token account...");
         // Create the token account
@@ -1458,7 +2882,7 @@ pub fn initialize_raid_program(
                 token_mint_account.clone(),
                 rent.clone(), // Added rent account
             ],
-            &[&[raid_program_token_account_seed, &[token_account_bump]]],
+            &[&[&raid_program_token_account_seed, &[token_account_bump]]],
         )?;
     }
 
@@ -1484,20 +2908,19 @@ pub fn initialize_raid_program(
         ],
     )?;
 
-    // Deserialize ProgramStateCard from account data using custom_deserialize
-    let (mut program_state, bytes_read) = ProgramStateCard::custom_deserialize(
-        &program_state_account.data.borrow(),
-    )
-    .map_err(|e| {
-        msg!("Failed to deserialize ProgramStateCard: {:?}", e);
-        ProgramError::InvalidAccountData
-    })?;
-
     let total_space = program_state_account.data.borrow().len();
 
     // Modify the program state
     msg!("Current ProgramStateCard: {:?}", program_state);
-    program_state.registered_programs_count += 1;
+    append_to_registry(
+        program_id,
+        pda::RAID_PROGRAMS_REGISTRY_SEED,
+        raid_programs_page_account,
+        user_account,
+        system_program,
+        &mut program_state.registered_programs_count,
+        *raid_program_data_account.key,
+    )?;
     msg!("Updated ProgramStateCard: {:?}", program_state);
 
     // Serialize the updated data to a temporary buffer
@@ -1552,73 +2975,21 @@ pub fn initialize_raid_program(
 
     msg!("Successfully updated ProgramStateCard");
 
-    // For ProgramRaidProgramsStateCard
-    let (mut raid_programs_state, raid_programs_bytes_read) =
-        ProgramRaidProgramsStateCard::custom_deserialize(
-            &program_raid_programs_state_account.data.borrow(),
-        )
-        .map_err(|e| {
-            msg!(
-                "Failed to deserialize ProgramRaidProgramsStateCard: {:?}",
-                e
-            );
-            ProgramError::InvalidAccountData
-        })?;
-
-    msg!(
-        "Current ProgramRaidProgramsStateCard: {:?}",
-        raid_programs_state
-    );
-    raid_programs_state
-        .raid_program_pubkeys
-        .push_str(&raid_program_data_account.key.to_string());
-    raid_programs_state.raid_program_pubkeys.push(',');
-    msg!(
-        "Updated ProgramRaidProgramsStateCard: {:?}",
-        raid_programs_state
-    );
-
-    // Serialize the updated ProgramRaidProgramsStateCard
-    let mut data = program_raid_programs_state_account.data.borrow_mut();
-    let bytes_written = raid_programs_state
-        .custom_serialize(&mut data)
-        .map_err(|e| {
-            msg!(
-                "Failed to serialize updated ProgramRaidProgramsStateCard: {:?}",
-                e
-            );
-            ProgramError::AccountDataTooSmall
-        })?;
-
-    // Handle potential size changes
-    if bytes_written > raid_programs_bytes_read {
-        msg!(
-            "New ProgramRaidProgramsStateCard data is larger. Using {} bytes of empty space.",
-            bytes_written - raid_programs_bytes_read
-        );
-    } else if bytes_written < raid_programs_bytes_read {
-        msg!(
-            "New ProgramRaidProgramsStateCard data is smaller. Clearing {} bytes of unused space.",
-            raid_programs_bytes_read - bytes_written
-        );
-        data[bytes_written..raid_programs_bytes_read].fill(0);
-    }
-
     // For UserCard
-    let (mut user_card, user_card_bytes_read) =
-        UserCard::custom_deserialize(&user_card_account.data.borrow()).map_err(|e| {
-            msg!("Failed to deserialize UserCard: {:?}", e);
-            ProgramError::InvalidAccountData
-        })?;
 
     msg!("Current UserCard: {:?}", user_card);
     user_card
         .owned_programs
-        .push_str(&raid_program_data_account.key.to_string());
-    user_card.owned_programs.push(',');
+        .push(*raid_program_data_account.key);
     msg!("Updated UserCard: {:?}", user_card);
 
     // Serialize the updated UserCard
+    ensure_account_space(
+        user_card_account,
+        user_account,
+        system_program,
+        user_card.stored_len()?,
+    )?;
     let mut data = user_card_account.data.borrow_mut();
     let bytes_written = user_card.custom_serialize(&mut data).map_err(|e| {
         msg!("Failed to serialize updated UserCard: {:?}", e);
@@ -1653,220 +3024,443 @@ pub fn initialize_raid_program(
     Ok(())
 }
 
-fn burn_tokens_for_raid(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    burn_amount: u64,
-) -> ProgramResult {
+fn add_stake(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let user_account = next_account_info(account_info_iter)?; // User's main account (signer)
-    let user_card_account = next_account_info(account_info_iter)?; // User's card account
-    let burn_card_account = next_account_info(account_info_iter)?; // Account to store burn information
-    let token_program = next_account_info(account_info_iter)?; // SPL Token program
-    let token_mint_account = next_account_info(account_info_iter)?; // Token mint account
-    let system_program = next_account_info(account_info_iter)?; // System program
-    let rent = next_account_info(account_info_iter)?; // Rent sysvar
-    let raid_program_account = next_account_info(account_info_iter)?; // Account storing raid program data
-    let competition_account = next_account_info(account_info_iter)?; // Account storing competition data
+    let user_account = next_account_info(account_info_iter)?.signer()?;
+    let raid_program_account = next_account_info(account_info_iter)?
+        .card::<RaidProgramCard>(program_id)?
+        .writable()?;
+    let raid_program_token_account = next_account_info(account_info_iter)?.writable()?;
+    let user_token_account = next_account_info(account_info_iter)?.writable()?;
+    let token_mint_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?.token_program()?;
+    let config_account = next_account_info(account_info_iter)?;
 
-    // Verify user is signer
-    if !user_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    let config = GlobalConfig::load(program_id, config_account)?;
+
+    if amount == 0 {
+        return Err(BullPosterError::ZeroAmount.into());
     }
 
-    // Obtain raid program id
-    let raid_program_data: RaidProgramCard =
-        RaidProgramCard::try_from_slice(&raid_program_account.data.borrow())?;
-    let raid_program_id = raid_program_data.raid_program_id;
+    // Verify the raid program account belongs to the signer
+    let (mut raid_program_data, raid_prog_bytes_read) =
+        RaidProgramCard::custom_deserialize(&raid_program_account.data.borrow())?;
+    if raid_program_data.user_key != *user_account.key {
+        return Err(BullPosterError::NotRaidProgramOwner.into());
+    }
 
-    // Obtain competition card data and verify that raid id is inclunded in enrolled programs
-    let competition_data: CompetitionCard =
-        CompetitionCard::try_from_slice(&competition_account.data.borrow())?;
-    let enrolled_programs: Vec<&str> = competition_data.enrolled_programs.split(',').collect();
-    if !enrolled_programs.contains(&raid_program_id.to_string().as_str()) {
-        return Err(ProgramError::InvalidAccountData);
+    // Verify the token mint
+    token_mint_account.token_mint(program_id, config.token_mint_bump)?;
+
+    // Derive PDA for the raid program's stake token account
+    let raid_program_token_account_pda = pda::create_address(
+        program_id,
+        &[&pda::raid_program_token_account_seed(
+            raid_program_account.key,
+        )],
+        raid_program_data.token_account_bump,
+    )?;
+    if raid_program_token_account.key != &raid_program_token_account_pda {
+        return Err(BullPosterError::InvalidStakeAccount.into());
     }
 
-    // Verify competition card account is active and has not been completed
-    if competition_data.status != "active" {
-        return Err(ProgramError::InvalidAccountData);
+    msg!("Adding {} tokens to stake", amount);
+    invoke(
+        &token_instruction::transfer_checked(
+            token_program.key,
+            user_token_account.key,
+            token_mint_account.key,
+            raid_program_token_account.key,
+            user_account.key,
+            &[],
+            amount,
+            9,
+        )?,
+        &[
+            user_token_account.clone(),
+            token_mint_account.clone(),
+            raid_program_token_account.clone(),
+            user_account.clone(),
+            token_program.clone(),
+        ],
+    )?;
+
+    // Record the vault balance rather than adding to the old value, which also
+    // backfills cards created before staked_amount was tracked
+    raid_program_data.staked_amount =
+        StateWithExtensions::<Account>::unpack(&raid_program_token_account.data.borrow())?
+            .base
+            .amount;
+    if raid_program_data.staked_amount >= config.required_stake_amount {
+        raid_program_data.is_deactivated = false;
     }
 
-    // If active verify not past end time
-    if Clock::get()?.unix_timestamp as u64 >= competition_data.end_time {
-        return Err(ProgramError::InvalidAccountData);
+    let mut data = raid_program_account.data.borrow_mut();
+    let bytes_written = raid_program_data.custom_serialize(&mut data)?;
+    if bytes_written < raid_prog_bytes_read {
+        data[bytes_written..raid_prog_bytes_read].fill(0);
     }
 
-    let user_card_data: UserCard = UserCard::try_from_slice(&user_card_account.data.borrow())?;
-    if user_card_data.user_pubkey != *user_account.key {
-        return Err(ProgramError::InvalidAccountData);
+    msg!(
+        "Stake is now {} ({:?} tier)",
+        raid_program_data.staked_amount,
+        StakeTier::from_stake(raid_program_data.staked_amount, &config)
+    );
+    Ok(())
+}
+
+fn request_unstake(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let user_account = next_account_info(account_info_iter)?.signer()?;
+    let raid_program_account = next_account_info(account_info_iter)?
+        .card::<RaidProgramCard>(program_id)?
+        .writable()?;
+    let raid_program_token_account = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+
+    let config = GlobalConfig::load(program_id, config_account)?;
+
+    // Verify the raid program account belongs to the signer
+    let (mut raid_program_data, raid_prog_bytes_read) =
+        RaidProgramCard::custom_deserialize(&raid_program_account.data.borrow())?;
+    if raid_program_data.user_key != *user_account.key {
+        return Err(BullPosterError::NotRaidProgramOwner.into());
     }
-    if !user_card_data
-        .enrolled_programs
-        .contains(&raid_program_id.to_string())
-    {
-        return Err(ProgramError::InvalidAccountData);
+
+    // Derive PDA for the raid program's stake token account
+    let raid_program_token_account_pda = pda::create_address(
+        program_id,
+        &[&pda::raid_program_token_account_seed(
+            raid_program_account.key,
+        )],
+        raid_program_data.token_account_bump,
+    )?;
+    if raid_program_token_account.key != &raid_program_token_account_pda {
+        return Err(BullPosterError::InvalidStakeAccount.into());
+    }
+
+    let staked_amount =
+        StateWithExtensions::<Account>::unpack(&raid_program_token_account.data.borrow())?
+            .base
+            .amount;
+    if amount == 0 || amount > staked_amount {
+        return Err(BullPosterError::InsufficientTokens.into());
     }
 
-    // Verify that burn card account is not initialized (empty)
-    if !burn_card_account.data_is_empty() {
-        return Err(ProgramError::AccountAlreadyInitialized);
+    // A new request replaces any pending one and restarts the cooldown
+    raid_program_data.pending_unstake_amount = amount;
+    raid_program_data.unstake_available_at =
+        Clock::get()?.unix_timestamp as u64 + config.unstake_cooldown;
+
+    let mut data = raid_program_account.data.borrow_mut();
+    let bytes_written = raid_program_data.custom_serialize(&mut data)?;
+    if bytes_written < raid_prog_bytes_read {
+        data[bytes_written..raid_prog_bytes_read].fill(0);
     }
 
-    // Derive PDA for burn account
-    let burn_seed = format!(
-        "burn_{}_{}_{}",
-        user_account.key,
-        raid_program_id,
-        Clock::get()?.unix_timestamp as u64
+    msg!(
+        "Unstake of {} tokens requested, available at {}",
+        amount,
+        raid_program_data.unstake_available_at
     );
-    let (burn_card_account_pda, bump) =
-        Pubkey::find_program_address(&[burn_seed.as_bytes()], program_id);
+    Ok(())
+}
+
+fn withdraw_stake(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let user_account = next_account_info(account_info_iter)?.signer()?;
+    let raid_program_account = next_account_info(account_info_iter)?
+        .card::<RaidProgramCard>(program_id)?
+        .writable()?;
+    let raid_program_token_account = next_account_info(account_info_iter)?.writable()?;
+    let user_token_account = next_account_info(account_info_iter)?.writable()?;
+    let token_mint_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?.token_program()?;
+    let config_account = next_account_info(account_info_iter)?;
+
+    let config = GlobalConfig::load(program_id, config_account)?;
 
-    // Verify that the provided burn_card_account matches the derived PDA
-    if burn_card_account.key != &burn_card_account_pda {
-        return Err(ProgramError::InvalidAccountData);
+    // Verify the raid program account belongs to the signer
+    let (mut raid_program_data, raid_prog_bytes_read) =
+        RaidProgramCard::custom_deserialize(&raid_program_account.data.borrow())?;
+    if raid_program_data.user_key != *user_account.key {
+        return Err(BullPosterError::NotRaidProgramOwner.into());
     }
 
-    // Create burn card account
-    let burn_space = BurnCard {
-        raid_program_id: raid_program_id.clone(),
-        competition_id: competition_data.competition_id.clone(),
-        user_id: *user_account.key,
-        burn_amount,
-        timestamp: Clock::get()?.unix_timestamp as u64,
-    };
+    let amount = raid_program_data.pending_unstake_amount;
+    if amount == 0 {
+        msg!("No unstake has been requested");
+        return Err(BullPosterError::NoPendingUnstake.into());
+    }
 
-    let space = 1000;
+    if (Clock::get()?.unix_timestamp as u64) < raid_program_data.unstake_available_at {
+        msg!(
+            "Stake cannot be withdrawn before {}",
+            raid_program_data.unstake_available_at
+        );
+        return Err(BullPosterError::UnstakeCooldownActive.into());
+    }
 
-    // let lamports = Rent::get()?.minimum_balance(space);
+    // Stake backs the program while it raids, so it stays locked until the raid is over
+    if raid_program_data.is_conducting_raid {
+        msg!("Cannot withdraw stake while the program is conducting a raid");
+        return Err(BullPosterError::StakeLockedDuringRaid.into());
+    }
 
-    create_pda_account(
-        user_account,                     // Payer for account creation
-        &Rent::get()?,                    // Lamports for rent exemption
-        space,                            // Size of the account
-        token_program.key,                // Owner of the new account
-        system_program,                   // System program for creating account
-        burn_card_account,                // The account being created
-        &[burn_seed.as_bytes(), &[bump]], // PDA seeds
+    // Verify the token mint
+    token_mint_account.token_mint(program_id, config.token_mint_bump)?;
+
+    // Derive PDA for the raid program's stake token account
+    let token_account_bump = raid_program_data.token_account_bump;
+    let raid_program_token_account_seed =
+        pda::raid_program_token_account_seed(raid_program_account.key);
+    let raid_program_token_account_pda = pda::create_address(
+        program_id,
+        &[&raid_program_token_account_seed],
+        token_account_bump,
     )?;
+    if raid_program_token_account.key != &raid_program_token_account_pda {
+        return Err(BullPosterError::InvalidStakeAccount.into());
+    }
+
+    let staked_amount =
+        StateWithExtensions::<Account>::unpack(&raid_program_token_account.data.borrow())?
+            .base
+            .amount;
+    if amount > staked_amount {
+        return Err(BullPosterError::InsufficientTokens.into());
+    }
 
-    // Initialize the burn card account
+    msg!("Withdrawing {} staked tokens", amount);
     invoke_signed(
-        &token_instruction::initialize_account3(
-            token_program.key,      // Token program ID
-            burn_card_account.key,  // Account to initialize
-            token_mint_account.key, // Mint of the token
-            &burn_card_account_pda, // Authority of the account
+        &token_instruction::transfer_checked(
+            token_program.key,
+            raid_program_token_account.key,
+            token_mint_account.key,
+            user_token_account.key,
+            &raid_program_token_account_pda,
+            &[],
+            amount,
+            9,
         )?,
         &[
-            burn_card_account.clone(),
+            raid_program_token_account.clone(),
             token_mint_account.clone(),
-            burn_card_account.clone(),
-            rent.clone(),
+            user_token_account.clone(),
+            raid_program_token_account.clone(),
+            token_program.clone(),
         ],
-        &[&[burn_seed.as_bytes(), &[bump]]],
+        &[&[&raid_program_token_account_seed, &[token_account_bump]]],
     )?;
 
-    // Transfer tokens from user to burn account
-    msg!("Transferring {} tokens to burn account", burn_amount);
-    invoke(
-        &token_instruction::transfer(
-            token_program.key,     // Token program ID
-            user_account.key,      // User's token account (source)
-            burn_card_account.key, // Burn card account (destination)
-            user_account.key,      // User's account (payer and authority)
-            &[],                   // No additional signers
-            burn_amount,           // Amount of tokens to transfer to burn account
-        )?,
-        &[
-            user_account.clone(),      // User's token account (source of tokens)
-            burn_card_account.clone(), // Burn card account (destination)
-            user_account.clone(),      // User's account (authority)
-            token_program.clone(),     // Token program for managing token operations
-        ],
-    )?;
+    raid_program_data.pending_unstake_amount = 0;
+    raid_program_data.unstake_available_at = 0;
+    raid_program_data.staked_amount = staked_amount - amount;
+    if raid_program_data.staked_amount < config.required_stake_amount {
+        msg!("Stake below requirement, deactivating raid program");
+        raid_program_data.is_deactivated = true;
+    }
 
-    // Burn tokens
-    msg!("Burning {} tokens", burn_amount);
-    invoke_signed(
-        &token_instruction::burn(
-            token_program.key,      // Token program ID
-            burn_card_account.key,  // Burn card account (from where the tokens are burned)
-            &burn_card_account_pda, // PDA that has authority over the burn account
-            user_account.key,       // User's account (authority for burn action)
-            &[],                    // No additional signers
-            burn_amount,            // Amount of tokens to burn
-        )?,
-        &[
-            burn_card_account.clone(), // Burn card account (source of tokens to burn)
-            burn_card_account.clone(), // PDA of the burn card account
-            user_account.clone(),      // User's account (authority)
-            token_program.clone(),     // Token program managing the burn operation
-        ],
-        &[&[burn_seed.as_bytes(), &[bump]]],
-    )?;
-    // Store burn data
-
-    let burn_data = BurnCard {
-        raid_program_id: raid_program_id.clone(),
-        competition_id: competition_data.competition_id.clone(),
-        user_id: *user_account.key,
-        burn_amount,
-        timestamp: Clock::get()?.unix_timestamp as u64,
-    };
-    burn_data.serialize(&mut &mut burn_card_account.data.borrow_mut()[..])?;
+    let mut data = raid_program_account.data.borrow_mut();
+    let bytes_written = raid_program_data.custom_serialize(&mut data)?;
+    if bytes_written < raid_prog_bytes_read {
+        data[bytes_written..raid_prog_bytes_read].fill(0);
+    }
 
-    msg!("Tokens burned successfully");
+    msg!("Stake withdrawn successfully");
     Ok(())
 }
 
-fn create_raid(
+fn burn_tokens_for_raid(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    competition_type: String,
+    burn_amount: u64,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let user_account = next_account_info(account_info_iter)?;
-    let current_competition_account = next_account_info(account_info_iter)?;
-    let new_competition_account = next_account_info(account_info_iter)?;
-    let raid_program_account = next_account_info(account_info_iter)?;
-    let system_program = next_account_info(account_info_iter)?;
-    let current_raid_card_account = next_account_info(account_info_iter)?;
-    let new_raid_card_account = next_account_info(account_info_iter)?;
-    let program_state_account = next_account_info(account_info_iter)?;
-    let program_raids_state_account = next_account_info(account_info_iter)?;
-    let program_competitions_state_account = next_account_info(account_info_iter)?;
-
-    // Verify user is signer
-    if !user_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    let user_account = next_account_info(account_info_iter)?.signer()?.writable()?; // User's main account (signer)
+    let user_card_account = next_account_info(account_info_iter)?.card::<UserCard>(program_id)?; // User's card account
+    let burn_ledger_account = next_account_info(account_info_iter)?.writable()?; // User's burn ledger for this raid
+    let user_token_account = next_account_info(account_info_iter)?.writable()?; // User's token account (source of the burn)
+    let token_program = next_account_info(account_info_iter)?.token_program()?; // SPL Token program
+    let token_mint_account = next_account_info(account_info_iter)?.writable()?; // Token mint account
+    let system_program = next_account_info(account_info_iter)?.system_program()?; // System program
+    let raid_program_account =
+        next_account_info(account_info_iter)?.card::<RaidProgramCard>(program_id)?; // Account storing raid program data
+    let competition_account =
+        next_account_info(account_info_iter)?.card::<CompetitionCard>(program_id)?; // Account storing competition data
+    let raid_card_account = next_account_info(account_info_iter)?
+        .card::<RaidCard>(program_id)?
+        .writable()?; // Raid card accumulating burn totals
+    let config_account = next_account_info(account_info_iter)?; // Global config
+
+    let config = GlobalConfig::load(program_id, config_account)?;
+
+    if burn_amount == 0 {
+        return Err(BullPosterError::ZeroAmount.into());
     }
 
-    // Verify program state account
-    let (program_state_pda, _) = Pubkey::find_program_address(&[b"program_state"], program_id);
-    if program_state_account.key != &program_state_pda {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    // Verify the token mint
+    token_mint_account.token_mint(program_id, config.token_mint_bump)?;
 
-    // Verify program raids state account
-    let (program_raids_state_pda, _) =
-        Pubkey::find_program_address(&[b"program_raids_state"], program_id);
-    if program_raids_state_account.key != &program_raids_state_pda {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    // Obtain raid program id
+    let (raid_program_data, _) =
+        RaidProgramCard::custom_deserialize(&raid_program_account.data.borrow())?;
+    let raid_program_id = raid_program_data.raid_program_id;
 
-    // Verify program competitions state account
-    let (program_competitions_state_pda, _) =
-        Pubkey::find_program_address(&[b"program_competitions_state"], program_id);
-    if program_competitions_state_account.key != &program_competitions_state_pda {
-        return Err(ProgramError::InvalidAccountData);
+    // Obtain competition card data and verify that raid id is inclunded in enrolled programs
+    let (competition_data, _) =
+        CompetitionCard::custom_deserialize(&competition_account.data.borrow())?;
+    if !competition_data
+        .enrolled_programs
+        .contains(&raid_program_id)
+    {
+        return Err(BullPosterError::RaidProgramNotEnrolled.into());
     }
 
-    // Deserialize ProgramStateCard from account data using custom_deserialize
-    let mut data_slice = &program_state_account.data.borrow()[..];
-    let initial_len = data_slice.len();
+    // Verify competition card account is active and has not been completed
+    if competition_data.status != "active" {
+        return Err(BullPosterError::InvalidCompetitionStatus.into());
+    }
+
+    // If active verify not past end time
+    if Clock::get()?.unix_timestamp as u64 >= competition_data.end_time {
+        return Err(BullPosterError::CompetitionEnded.into());
+    }
+
+    let (user_card_data, _) = UserCard::custom_deserialize(&user_card_account.data.borrow())?;
+    if user_card_data.user_pubkey != *user_account.key {
+        return Err(BullPosterError::NotUserCardOwner.into());
+    }
+    if !user_card_data.enrolled_programs.contains(&raid_program_id) {
+        return Err(BullPosterError::UserNotEnrolled.into());
+    }
+
+    // The burn counts towards the raid's running total used to rank the competition
+    let (mut raid_card_data, raid_card_bytes_read) =
+        RaidCard::custom_deserialize(&raid_card_account.data.borrow())?;
+    if raid_card_data.competition_id != *competition_account.key
+        || raid_card_data.raid_program_id != *raid_program_account.key
+    {
+        return Err(BullPosterError::RaidCardMismatch.into());
+    }
+
+    // Every burn by this user for this raid accumulates in one ledger, created on the first burn
+    let burn_timestamp = Clock::get()?.unix_timestamp as u64;
+    let mut burn_ledger = if burn_ledger_account.data_is_empty() {
+        let (burn_ledger_pda, bump) =
+            pda::burn_ledger_address(program_id, user_account.key, raid_card_account.key);
+        burn_ledger_account.address(&burn_ledger_pda, BullPosterError::InvalidBurnLedgerAccount)?;
+
+        let burn_ledger = BurnLedger {
+            user_id: *user_account.key,
+            raid_id: *raid_card_account.key,
+            raid_program_id,
+            competition_id: *competition_account.key,
+            total_burned: 0,
+            burn_count: 0,
+            last_burn_ts: 0,
+            bump,
+        };
+
+        create_pda_account(
+            user_account,
+            &Rent::get()?,
+            burn_ledger.stored_len()?,
+            program_id,
+            system_program,
+            burn_ledger_account,
+            &[
+                &pda::burn_ledger_seed(user_account.key, raid_card_account.key),
+                &[bump],
+            ],
+        )?;
+        burn_ledger
+    } else {
+        burn_ledger_account.card::<BurnLedger>(program_id)?;
+        let burn_ledger = BurnLedger::read_card(&burn_ledger_account.data.borrow())?;
+        let burn_ledger_pda = pda::create_address(
+            program_id,
+            &[&pda::burn_ledger_seed(
+                user_account.key,
+                raid_card_account.key,
+            )],
+            burn_ledger.bump,
+        )?;
+        burn_ledger_account.address(&burn_ledger_pda, BullPosterError::InvalidBurnLedgerAccount)?;
+        burn_ledger
+    };
+
+    // Burn straight from the user's token account; the user signs as its owner
+    msg!("Burning {} tokens", burn_amount);
+    invoke(
+        &token_instruction::burn_checked(
+            token_program.key,
+            user_token_account.key,
+            token_mint_account.key,
+            user_account.key,
+            &[],
+            burn_amount,
+            9,
+        )?,
+        &[
+            user_token_account.clone(),
+            token_mint_account.clone(),
+            user_account.clone(),
+            token_program.clone(),
+        ],
+    )?;
+
+    burn_ledger.total_burned = burn_ledger
+        .total_burned
+        .checked_add(burn_amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    burn_ledger.burn_count += 1;
+    burn_ledger.last_burn_ts = burn_timestamp;
+    burn_ledger.write_card(&mut burn_ledger_account.data.borrow_mut())?;
+
+    raid_card_data.total_burned = raid_card_data
+        .total_burned
+        .checked_add(burn_amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let mut data = raid_card_account.data.borrow_mut();
+    let bytes_written = raid_card_data.custom_serialize(&mut data)?;
+    if bytes_written < raid_card_bytes_read {
+        data[bytes_written..raid_card_bytes_read].fill(0);
+    }
+
+    msg!(
+        "Tokens burned successfully, {} burned by this user for the raid",
+        burn_ledger.total_burned
+    );
+    Ok(())
+}
 
+fn create_raid(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    competition_type: String,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let user_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let current_competition_account = next_account_info(account_info_iter)?.writable()?;
+    let new_competition_account = next_account_info(account_info_iter)?.writable()?;
+    let raid_program_account = next_account_info(account_info_iter)?
+        .card::<RaidProgramCard>(program_id)?
+        .writable()?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+    let current_raid_card_account = next_account_info(account_info_iter)?.writable()?;
+    let new_raid_card_account = next_account_info(account_info_iter)?.writable()?;
+    let program_state_account = next_account_info(account_info_iter)?
+        .card::<ProgramStateCard>(program_id)?
+        .writable()?;
+    let raids_page_account = next_account_info(account_info_iter)?.writable()?;
+    let competitions_page_account = next_account_info(account_info_iter)?.writable()?;
+    let config_account = next_account_info(account_info_iter)?;
+
+    let config = GlobalConfig::load(program_id, config_account)?;
+
+    // Deserialize ProgramStateCard from account data using custom_deserialize
     let (mut program_state, state_bytes_read) = ProgramStateCard::custom_deserialize(
         &program_state_account.data.borrow(),
     )
@@ -1875,25 +3469,16 @@ fn create_raid(
         ProgramError::InvalidAccountData
     })?;
 
-    // Log cursor position (how many bytes were read)
-    let cursor_position = initial_len - data_slice.len();
-    msg!("Cursor position after deserialization: {}", cursor_position);
-
-    // Calculate and log unused space in the account
-    let unused_space = program_state_account.data.borrow().len() - cursor_position;
-    msg!(
-        "Unused space in account after deserialization: {}",
-        unused_space
-    );
+    // Verify program state account with its stored bump
+    let program_state_pda =
+        pda::create_address(program_id, &[pda::PROGRAM_STATE_SEED], program_state.bump)?;
+    if program_state_account.key != &program_state_pda {
+        return Err(BullPosterError::InvalidProgramStateAccount.into());
+    }
 
     // Fetch and update sequence number for competition type from `last_seen_raids`
-    let mut last_seen_raids: serde_json::Value =
-        serde_json::from_str(&program_state.last_seen_raids).unwrap_or_else(|_| json!({}));
-
-    let current_sequence = last_seen_raids[&competition_type]
-        .get("sequence")
-        .and_then(|s| s.as_u64())
-        .unwrap_or(0);
+    let type_index = competition_type_index(&competition_type)?;
+    let current_sequence = program_state.last_seen_raids[type_index].sequence;
 
     let new_sequence = current_sequence + 1;
 
@@ -1901,34 +3486,42 @@ fn create_raid(
     let (mut raid_program_data, raid_program_bytes_read) =
         RaidProgramCard::custom_deserialize(&raid_program_account.data.borrow())?;
     if raid_program_data.user_key != *user_account.key {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(BullPosterError::NotRaidProgramOwner.into());
+    }
+
+    // Programs whose stake dropped below the requirement cannot raid
+    if raid_program_data.is_deactivated {
+        msg!("Raid program is deactivated");
+        return Err(BullPosterError::RaidProgramDeactivated.into());
+    }
+
+    // Bigger competitions require a bigger stake
+    if StakeTier::from_stake(raid_program_data.staked_amount, &config)
+        < StakeTier::required_for(&competition_type)?
+    {
+        msg!(
+            "Stake of {} is too low for {} competitions",
+            raid_program_data.staked_amount,
+            competition_type
+        );
+        return Err(BullPosterError::InsufficientTokens.into());
     }
 
     // Handle competition creation or joining
     let (competition_account, is_new_competition, raid_card_account) = if competition_type == "PvP"
     {
-        let challenged_program_account = next_account_info(account_info_iter)?;
+        let challenged_program_account =
+            next_account_info(account_info_iter)?.card::<RaidProgramCard>(program_id)?;
 
         if challenged_program_account.key == raid_program_account.key {
-            return Err(ProgramError::InvalidAccountData);
+            return Err(BullPosterError::CannotChallengeSelf.into());
         }
 
-        let competition_seed = format!(
-            "{}_{}_{}_{}",
-            raid_program_account.key.to_string(),
-            competition_type,
-            new_sequence,
-            challenged_program_account.key.to_string(),
-        );
-        let mut hasher = Sha256::new();
-        hasher.update(competition_seed.as_bytes());
-        let result = hasher.finalize();
-        let hashed_seed = &result[..32];
-
-        last_seen_raids[&competition_type] = json!({
-            "sequence": new_sequence,
-            "competition_id": hashed_seed,
-        });
+        // create_new_competition checks new_competition_account against the PDA for this sequence
+        program_state.last_seen_raids[type_index] = LastSeenRaid {
+            sequence: new_sequence,
+            competition_id: *new_competition_account.key,
+        };
 
         create_new_competition(
             program_id,
@@ -1936,34 +3529,45 @@ fn create_raid(
                 user_account.clone(),
                 system_program.clone(),
                 new_competition_account.clone(),
-                program_competitions_state_account.clone(),
+                competitions_page_account.clone(),
             ],
             new_sequence,
             &competition_type,
             Some(raid_program_account.key.clone()),
             Some(challenged_program_account.key.clone()),
+            &config,
+            &mut program_state,
         )?;
 
         (new_competition_account, true, new_raid_card_account)
     } else {
-        let competition_data: CompetitionCard =
-            CompetitionCard::try_from_slice(&current_competition_account.data.borrow())?;
-
-        if current_competition_account.data_is_empty()
-            || competition_data.status != "awaiting"
-            || competition_data.enrolled_programs.split(',').count() as u64
-                >= competition_data.required_programs
-        {
-            let competition_seed = format!("{}_{}", competition_type, new_sequence);
-            let mut hasher = Sha256::new();
-            hasher.update(competition_seed.as_bytes());
-            let result = hasher.finalize();
-            let hashed_seed = &result[..32];
+        // Join the current competition only if it is still awaiting programs of this type.
+        // The current one is the last competition opened for the type, so older ones that
+        // are still awaiting cannot be joined instead.
+        let current_competition_id = program_state.last_seen_raids[type_index].competition_id;
+        let can_join = if current_competition_id == Pubkey::default() {
+            // No competition of this type has been opened yet
+            false
+        } else {
+            current_competition_account
+                .address(
+                    &current_competition_id,
+                    BullPosterError::InvalidCompetitionAccount,
+                )?
+                .card::<CompetitionCard>(program_id)?;
+            let (competition_data, _) =
+                CompetitionCard::custom_deserialize(&current_competition_account.data.borrow())?;
+            competition_data.competition_type == competition_type
+                && competition_data.status == "awaiting"
+                && (competition_data.enrolled_programs.len() as u64)
+                    < competition_data.required_programs
+        };
 
-            last_seen_raids[&competition_type] = json!({
-                "sequence": new_sequence,
-                "competition_id": hashed_seed,
-            });
+        if !can_join {
+            program_state.last_seen_raids[type_index] = LastSeenRaid {
+                sequence: new_sequence,
+                competition_id: *new_competition_account.key,
+            };
 
             create_new_competition(
                 program_id,
@@ -1971,12 +3575,14 @@ fn create_raid(
                     user_account.clone(),
                     system_program.clone(),
                     new_competition_account.clone(),
-                    program_competitions_state_account.clone(),
+                    competitions_page_account.clone(),
                 ],
                 new_sequence,
                 &competition_type,
                 None,
                 None,
+                &config,
+                &mut program_state,
             )?;
 
             (new_competition_account, true, new_raid_card_account)
@@ -1990,51 +3596,35 @@ fn create_raid(
     };
 
     if is_new_competition {
-        // Update ProgramStateCard
-        program_state.last_seen_raids = serde_json::to_string(&last_seen_raids).unwrap();
-
-        // Serialize the updated ProgramStateCard
-        let mut data = program_state_account.data.borrow_mut();
-        let bytes_written = program_state.custom_serialize(&mut data).map_err(|e| {
-            msg!("Failed to serialize updated ProgramStateCard: {:?}", e);
-            ProgramError::AccountDataTooSmall
-        })?;
-
-        // Handle potential size changes
-        if bytes_written > state_bytes_read {
-            msg!(
-                "New ProgramStateCard data is larger. Using {} bytes of empty space.",
-                bytes_written - state_bytes_read
-            );
-        } else if bytes_written < state_bytes_read {
-            msg!(
-                "New ProgramStateCard data is smaller. Clearing {} bytes of unused space.",
-                state_bytes_read - bytes_written
-            );
-            data[bytes_written..state_bytes_read].fill(0);
-        }
-
         msg!("New competition created successfully");
     } else {
         // Verify that the current competition account exists
         if current_competition_account.data_is_empty() {
-            return Err(ProgramError::UninitializedAccount);
+            return Err(BullPosterError::CompetitionNotFound.into());
         }
+    }
 
+    // Enroll the raid program in the competition, starting it once all programs have joined
+    {
         let (mut competition_data, comp_bytes_read) =
             CompetitionCard::custom_deserialize(&competition_account.data.borrow())?;
         competition_data
             .enrolled_programs
-            .push_str(&format!("{},", raid_program_account.key.to_string()));
+            .push(*raid_program_account.key);
 
-        if competition_data.enrolled_programs.split(',').count() as u64
-            >= competition_data.required_programs
-        {
+        if competition_data.enrolled_programs.len() as u64 >= competition_data.required_programs {
             competition_data.status = "active".to_string();
-            competition_data.start_time = Clock::get()?.unix_timestamp as u64 + 300; // 5 minutes from now
-            competition_data.end_time = competition_data.start_time + 1200; // End in 20 minutes after start
+            competition_data.start_time =
+                Clock::get()?.unix_timestamp as u64 + config.raid_start_delay;
+            competition_data.end_time = competition_data.start_time + config.raid_duration;
         }
 
+        ensure_account_space(
+            competition_account,
+            user_account,
+            system_program,
+            competition_data.stored_len()?,
+        )?;
         let mut data = competition_account.data.borrow_mut();
         let bytes_written = competition_data.custom_serialize(&mut data)?;
 
@@ -2052,65 +3642,32 @@ fn create_raid(
             data[bytes_written..comp_bytes_read].fill(0);
         }
 
-        msg!("Joined existing competition successfully");
+        msg!("Enrolled raid program in competition successfully");
     }
 
     // Create raid account
-    let raid_seed = format!(
-        "raid_{}_{}",
-        competition_account.key.to_string(),
-        raid_program_account.key.to_string()
-    );
-
-    let mut hasher = Sha256::new();
-    hasher.update(raid_seed.as_bytes());
-    let result = hasher.finalize();
-    let hashed_raid_seed = &result[..32];
-
-    let (raid_account_pda, bump) = Pubkey::find_program_address(&[&hashed_raid_seed], program_id);
-
-    // Verify that the provided raid_card_account matches the derived PDA
-    if raid_card_account.key != &raid_account_pda {
-        return Err(ProgramError::InvalidAccountData);
-    }
-
-    // Check if the raid card account already exists
-    if !raid_card_account.data_is_empty() {
-        return Err(ProgramError::AccountAlreadyInitialized);
-    }
-
-    let raid_space = RaidCard {
-        competition_id: competition_account.key.clone(),
-        raid_program_id: raid_program_account.key.clone(),
-        raid_id: raid_card_account.key.clone(),
-        distributed_rewards: String::new(),
-        placements: String::new(),
-    };
-
-    let space = 1000;
-
-    create_pda_account(
-        user_account,
-        &Rent::get()?,
-        space,
+    create_raid_card(
         program_id,
+        user_account,
         system_program,
         raid_card_account,
-        &[&hashed_raid_seed, &[bump]],
+        competition_account.key,
+        raid_program_account.key,
     )?;
 
-    raid_space.serialize(&mut &mut raid_card_account.data.borrow_mut()[..])?;
-
     // Update RaidProgramCard
     let (mut raid_program_data, raid_prog_bytes_read) =
         RaidProgramCard::custom_deserialize(&raid_program_account.data.borrow())?;
     raid_program_data.is_conducting_raid = true;
     raid_program_data.active_raid_id = raid_card_account.key.clone();
-    raid_program_data
-        .raids
-        .push_str(&raid_card_account.key.to_string());
-    raid_program_data.raids.push(',');
+    raid_program_data.raids.push(*raid_card_account.key);
 
+    ensure_account_space(
+        raid_program_account,
+        user_account,
+        system_program,
+        raid_program_data.stored_len()?,
+    )?;
     let mut data = raid_program_account.data.borrow_mut();
     let bytes_written = raid_program_data.custom_serialize(&mut data)?;
 
@@ -2128,35 +3685,92 @@ fn create_raid(
         data[bytes_written..raid_prog_bytes_read].fill(0);
     }
 
-    // Update ProgramRaidsStateCard
-    let (mut program_raids_state, raids_state_bytes_read) =
-        ProgramRaidsStateCard::custom_deserialize(&program_raids_state_account.data.borrow())?;
-    program_raids_state
-        .raid_pubkeys
-        .push_str(&raid_card_account.key.to_string());
-    program_raids_state.raid_pubkeys.push(',');
+    // Register the raid and persist the updated head counters
+    append_to_registry(
+        program_id,
+        pda::RAIDS_REGISTRY_SEED,
+        raids_page_account,
+        user_account,
+        system_program,
+        &mut program_state.registered_raids_count,
+        *raid_card_account.key,
+    )?;
 
-    let mut data = program_raids_state_account.data.borrow_mut();
-    let bytes_written = program_raids_state.custom_serialize(&mut data)?;
+    let mut data = program_state_account.data.borrow_mut();
+    let bytes_written = program_state.custom_serialize(&mut data).map_err(|e| {
+        msg!("Failed to serialize updated ProgramStateCard: {:?}", e);
+        ProgramError::AccountDataTooSmall
+    })?;
 
     // Handle potential size changes
-    if bytes_written > raids_state_bytes_read {
+    if bytes_written > state_bytes_read {
         msg!(
-            "New ProgramRaidsStateCard data is larger. Using {} bytes of empty space.",
-            bytes_written - raids_state_bytes_read
+            "New ProgramStateCard data is larger. Using {} bytes of empty space.",
+            bytes_written - state_bytes_read
         );
-    } else if bytes_written < raids_state_bytes_read {
+    } else if bytes_written < state_bytes_read {
         msg!(
-            "New ProgramRaidsStateCard data is smaller. Clearing {} bytes of unused space.",
-            raids_state_bytes_read - bytes_written
+            "New ProgramStateCard data is smaller. Clearing {} bytes of unused space.",
+            state_bytes_read - bytes_written
         );
-        data[bytes_written..raids_state_bytes_read].fill(0);
+        data[bytes_written..state_bytes_read].fill(0);
     }
 
     msg!("Raid created and competition updated successfully");
     Ok(())
 }
 
+fn create_raid_card<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    raid_card_account: &AccountInfo<'a>,
+    competition_id: &Pubkey,
+    raid_program_id: &Pubkey,
+) -> ProgramResult {
+    let hashed_raid_seed = pda::raid_card_seed(competition_id, raid_program_id);
+    let (raid_account_pda, bump) =
+        pda::raid_card_address(program_id, competition_id, raid_program_id);
+
+    // Verify that the provided raid_card_account matches the derived PDA
+    if raid_card_account.key != &raid_account_pda {
+        return Err(BullPosterError::InvalidRaidCardAccount.into());
+    }
+
+    // Check if the raid card account already exists
+    if !raid_card_account.data_is_empty() {
+        return Err(BullPosterError::RaidCardAlreadyExists.into());
+    }
+
+    let raid_space = RaidCard {
+        competition_id: *competition_id,
+        raid_program_id: *raid_program_id,
+        raid_id: raid_card_account.key.clone(),
+        distributed_rewards: Vec::new(),
+        placements: Vec::new(),
+        total_burned: 0,
+        reward_amount: 0,
+        is_slashed: false,
+        bump,
+    };
+
+    let space = 1000;
+
+    create_pda_account(
+        payer,
+        &Rent::get()?,
+        space,
+        program_id,
+        system_program,
+        raid_card_account,
+        &[&hashed_raid_seed, &[bump]],
+    )?;
+
+    raid_space.custom_serialize(&mut raid_card_account.data.borrow_mut())?;
+
+    Ok(())
+}
+
 fn create_new_competition<'a>(
     program_id: &Pubkey,
     accounts: &[AccountInfo<'a>],
@@ -2164,12 +3778,14 @@ fn create_new_competition<'a>(
     competition_type: &str,
     challenger_program_id: Option<Pubkey>,
     challenged_program_id: Option<Pubkey>,
+    config: &GlobalConfig,
+    program_state: &mut ProgramStateCard,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let user_account = next_account_info(account_info_iter)?;
     let system_program = next_account_info(account_info_iter)?;
     let new_competition_account = next_account_info(account_info_iter)?;
-    let program_competitions_state_account = next_account_info(account_info_iter)?;
+    let competitions_page_account = next_account_info(account_info_iter)?;
 
     // Verify user is signer
     if !user_account.is_signer {
@@ -2177,38 +3793,30 @@ fn create_new_competition<'a>(
     }
 
     // Derive PDA for new competition account
-    let new_competition_seed = if competition_type == "PvP" {
-        format!(
-            "{}_{}_{}_{}",
+    let pvp_programs = if competition_type == "PvP" {
+        Some((
             challenger_program_id
-                .expect("Challenger program ID must be present for PvP competitions")
-                .to_string(),
-            competition_type,
-            sequence,
+                .as_ref()
+                .expect("Challenger program ID must be present for PvP competitions"),
             challenged_program_id
-                .expect("Challenged program ID must be present for PvP competitions")
-                .to_string(),
-        )
+                .as_ref()
+                .expect("Challenged program ID must be present for PvP competitions"),
+        ))
     } else {
-        format!("{}_{}", competition_type, sequence)
+        None
     };
-
-    let mut hasher = Sha256::new();
-    hasher.update(new_competition_seed.as_bytes());
-    let result = hasher.finalize();
-    let hashed_seed = &result[..32];
-
+    let hashed_seed = pda::competition_seed(competition_type, sequence, pvp_programs);
     let (new_competition_account_pda, bump) =
-        Pubkey::find_program_address(&[hashed_seed], program_id);
+        pda::competition_address(program_id, competition_type, sequence, pvp_programs);
 
     // Verify that the provided account matches the derived PDA
     if new_competition_account.key != &new_competition_account_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(BullPosterError::InvalidCompetitionAccount.into());
     }
 
     // Check if the new competition account already exists
     if !new_competition_account.data_is_empty() {
-        return Err(ProgramError::AccountAlreadyInitialized);
+        return Err(BullPosterError::CompetitionAlreadyExists.into());
     }
 
     // Create new competition account
@@ -2219,24 +3827,27 @@ fn create_new_competition<'a>(
         end_time: 0,
         total_rewards_distributed: 0,
         status: "awaiting".to_string(),
-        enrolled_programs: String::new(),
+        enrolled_programs: Vec::new(),
         required_programs: match competition_type {
             "4-program" => 4,
             "6-program" => 6,
             "12-program" => 12,
             "24-program" => 24,
             "PvP" => 2,
-            _ => return Err(ProgramError::InvalidArgument),
+            _ => return Err(BullPosterError::InvalidCompetitionType.into()),
         },
         challenger_program_id,
         challenged_program_id,
         start_expiration: if competition_type == "PvP" {
-            Some(Clock::get()?.unix_timestamp as u64 + 300) // 5 minutes from now
+            Some(Clock::get()?.unix_timestamp as u64 + config.pvp_acceptance_window)
         } else {
             None
         },
-        distributed_rewards: String::new(),
-        placements: String::new(),
+        distributed_rewards: Vec::new(),
+        placements: Vec::new(),
+        reward_cap: config
+            .competition_reward_cap(competition_type, Clock::get()?.unix_timestamp as u64)?,
+        reward_vault_bump: pda::reward_vault_address(program_id, new_competition_account.key).1,
     };
 
     let space = 1000;
@@ -2251,7 +3862,7 @@ fn create_new_competition<'a>(
             program_id,
             system_program,
             new_competition_account,
-            &[hashed_seed, &[bump]],
+            &[&hashed_seed, &[bump]],
         )?;
     }
 
@@ -2275,137 +3886,1840 @@ fn create_new_competition<'a>(
     */
 
     // Serialize new competition data
-    new_competition_space.serialize(&mut &mut new_competition_account.data.borrow_mut()[..])?;
+    new_competition_space.custom_serialize(&mut new_competition_account.data.borrow_mut())?;
 
-    // Update ProgramCompetitionsStateCard
-    let mut program_competitions_state = ProgramCompetitionsStateCard::try_from_slice(
-        &program_competitions_state_account.data.borrow(),
+    // Register the competition; the caller persists the advanced head counter
+    append_to_registry(
+        program_id,
+        pda::COMPETITIONS_REGISTRY_SEED,
+        competitions_page_account,
+        user_account,
+        system_program,
+        &mut program_state.registered_competitions_count,
+        *new_competition_account.key,
     )?;
-    program_competitions_state
-        .competition_pubkeys
-        .push_str(&new_competition_account.key.to_string());
-    program_competitions_state.competition_pubkeys.push(',');
-    program_competitions_state
-        .serialize(&mut &mut program_competitions_state_account.data.borrow_mut()[..])?;
 
     msg!("New competition created successfully");
     Ok(())
 }
 
-fn accept_pvp_challenge(accounts: &[AccountInfo]) -> ProgramResult {
+fn accept_pvp_challenge(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let user_account = next_account_info(account_info_iter)?;
-    let competition_account = next_account_info(account_info_iter)?;
-    let challenged_program_account = next_account_info(account_info_iter)?;
-
-    // Verify user is signer
-    if !user_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    let user_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let competition_account = next_account_info(account_info_iter)?
+        .card::<CompetitionCard>(program_id)?
+        .writable()?;
+    let challenged_program_account = next_account_info(account_info_iter)?
+        .card::<RaidProgramCard>(program_id)?
+        .writable()?;
+    let raid_card_account = next_account_info(account_info_iter)?.writable()?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+    let config_account = next_account_info(account_info_iter)?;
+
+    let config = GlobalConfig::load(program_id, config_account)?;
 
     // Verify program ownership
-    let challenged_program_data: RaidProgramCard =
-        RaidProgramCard::try_from_slice(&challenged_program_account.data.borrow())?;
+    let (mut challenged_program_data, challenged_bytes_read) =
+        RaidProgramCard::custom_deserialize(&challenged_program_account.data.borrow())?;
     if challenged_program_data.user_key != *user_account.key {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(BullPosterError::NotRaidProgramOwner.into());
+    }
+
+    // Programs whose stake dropped below the requirement cannot raid
+    if challenged_program_data.is_deactivated {
+        msg!("Raid program is deactivated");
+        return Err(BullPosterError::RaidProgramDeactivated.into());
+    }
+
+    if StakeTier::from_stake(challenged_program_data.staked_amount, &config)
+        < StakeTier::required_for("PvP")?
+    {
+        msg!(
+            "Stake of {} is too low for PvP competitions",
+            challenged_program_data.staked_amount
+        );
+        return Err(BullPosterError::InsufficientTokens.into());
     }
 
     // Deserialize competition data
-    let mut competition_data: CompetitionCard =
-        CompetitionCard::try_from_slice(&competition_account.data.borrow())?;
+    let (mut competition_data, comp_bytes_read) =
+        CompetitionCard::custom_deserialize(&competition_account.data.borrow())?;
 
     // Verify competition is awaiting acceptance
     if competition_data.status != "awaiting" {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(BullPosterError::InvalidCompetitionStatus.into());
     }
 
-    // Check if the challenge has expired
+    // Only the program that was challenged may accept
+    if competition_data.challenged_program_id != Some(*challenged_program_account.key) {
+        return Err(BullPosterError::NotChallengedProgram.into());
+    }
+
+    // An expired challenge cannot be accepted. A failed instruction keeps none of its
+    // writes, so the status is left for CheckRaidStatus to move to "expired".
     let current_time = Clock::get()?.unix_timestamp as u64;
     if current_time > competition_data.start_expiration.unwrap_or(0) {
-        competition_data.status = "expired".to_string();
-        competition_data.serialize(&mut &mut competition_account.data.borrow_mut()[..])?;
-        return Err(ProgramError::InvalidAccountData);
+        return Err(BullPosterError::ChallengeExpired.into());
     }
 
     // Update competition data
-    competition_data.challenged_program_id = Some(challenged_program_data.raid_program_id.clone());
+    competition_data
+        .enrolled_programs
+        .push(*challenged_program_account.key);
     competition_data.status = "active".to_string();
-    competition_data.start_time = Clock::get()?.unix_timestamp as u64 + 300;
-    competition_data.end_time = competition_data.start_time + 1200; // End in 20 minutes
+    competition_data.start_time = Clock::get()?.unix_timestamp as u64 + config.raid_start_delay;
+    competition_data.end_time = competition_data.start_time + config.raid_duration;
+
+    ensure_account_space(
+        competition_account,
+        user_account,
+        system_program,
+        competition_data.stored_len()?,
+    )?;
+    let mut data = competition_account.data.borrow_mut();
+    let bytes_written = competition_data.custom_serialize(&mut data)?;
+    if bytes_written < comp_bytes_read {
+        data[bytes_written..comp_bytes_read].fill(0);
+    }
+    drop(data);
 
-    competition_data.serialize(&mut &mut competition_account.data.borrow_mut()[..])?;
+    // Create the challenged program's raid card so its burns are tracked like the challenger's
+    create_raid_card(
+        program_id,
+        user_account,
+        system_program,
+        raid_card_account,
+        competition_account.key,
+        challenged_program_account.key,
+    )?;
 
     // Update challenged program data to indicate it is conducting a raid
-    let mut challenged_program_data: RaidProgramCard =
-        RaidProgramCard::try_from_slice(&challenged_program_account.data.borrow())?;
     challenged_program_data.is_conducting_raid = true;
-    challenged_program_data
-        .serialize(&mut &mut challenged_program_account.data.borrow_mut()[..])?;
+    challenged_program_data.active_raid_id = raid_card_account.key.clone();
+    challenged_program_data.raids.push(*raid_card_account.key);
+
+    ensure_account_space(
+        challenged_program_account,
+        user_account,
+        system_program,
+        challenged_program_data.stored_len()?,
+    )?;
+    let mut data = challenged_program_account.data.borrow_mut();
+    let bytes_written = challenged_program_data.custom_serialize(&mut data)?;
+    if bytes_written < challenged_bytes_read {
+        data[bytes_written..challenged_bytes_read].fill(0);
+    }
 
     msg!("PvP challenge accepted and competition started successfully");
     Ok(())
 }
 
-fn check_raid_status(//accounts: &[AccountInfo],
-    //raid_program_id: u64,
-) -> ProgramResult {
-    //let account_info_iter = &mut accounts.iter();
-    //let raid_account = next_account_info(account_info_iter)?;
+// Take a program out of a competition that never started, releasing the stake lock
+// that create_raid or accept_pvp_challenge set. Only finalize_competition releases it
+// otherwise, so this is the way out of a competition that never fills and of a PvP
+// challenge that expired. A challenger leaving an awaiting challenge withdraws it.
+// The raid card is closed, so the program can join the same competition again; no
+// burns are lost, as burning needs an active competition.
+fn leave_competition(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let user_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let raid_program_account = next_account_info(account_info_iter)?
+        .card::<RaidProgramCard>(program_id)?
+        .writable()?;
+    let competition_account = next_account_info(account_info_iter)?
+        .card::<CompetitionCard>(program_id)?
+        .writable()?;
+    let raid_card_account = next_account_info(account_info_iter)?
+        .card::<RaidCard>(program_id)?
+        .writable()?;
+
+    let (mut raid_program_data, raid_program_bytes_read) =
+        RaidProgramCard::custom_deserialize(&raid_program_account.data.borrow())?;
+    if raid_program_data.user_key != *user_account.key {
+        return Err(BullPosterError::NotRaidProgramOwner.into());
+    }
+
+    let (raid_card_data, _) = RaidCard::custom_deserialize(&raid_card_account.data.borrow())?;
+    if raid_card_data.competition_id != *competition_account.key
+        || raid_card_data.raid_program_id != *raid_program_account.key
+    {
+        return Err(BullPosterError::RaidCardMismatch.into());
+    }
+
+    let (mut competition_data, comp_bytes_read) =
+        CompetitionCard::custom_deserialize(&competition_account.data.borrow())?;
+    if !competition_data
+        .enrolled_programs
+        .contains(raid_program_account.key)
+        || !raid_program_data.is_conducting_raid
+        || raid_program_data.active_raid_id != *raid_card_account.key
+    {
+        return Err(BullPosterError::RaidProgramNotEnrolled.into());
+    }
+
+    if competition_data.status != "awaiting" && competition_data.status != "expired" {
+        return Err(BullPosterError::InvalidCompetitionStatus.into());
+    }
+
+    competition_data
+        .enrolled_programs
+        .retain(|key| key != raid_program_account.key);
+    if competition_data.competition_type == "PvP" {
+        competition_data.status = "expired".to_string();
+    }
 
-    // Deserialize raid data
-    //let raid_data: RaidCard = RaidCard::try_from_slice(&raid_account.data.borrow())?;
+    let mut data = competition_account.data.borrow_mut();
+    let bytes_written = competition_data.custom_serialize(&mut data)?;
+    if bytes_written < comp_bytes_read {
+        data[bytes_written..comp_bytes_read].fill(0);
+    }
+    drop(data);
 
-    // Check if the raid has ended
-    //let current_time = Clock::get()?.unix_timestamp as u64;
+    raid_program_data.is_conducting_raid = false;
+    raid_program_data.active_raid_id = Pubkey::default();
+    raid_program_data
+        .raids
+        .retain(|key| key != raid_card_account.key);
 
-    msg!("Raid status checked successfully");
+    let mut data = raid_program_account.data.borrow_mut();
+    let bytes_written = raid_program_data.custom_serialize(&mut data)?;
+    if bytes_written < raid_program_bytes_read {
+        data[bytes_written..raid_program_bytes_read].fill(0);
+    }
+    drop(data);
+
+    // Close the raid card, refunding its rent to the program owner
+    let refund = raid_card_account.lamports();
+    **user_account.lamports.borrow_mut() = user_account
+        .lamports()
+        .checked_add(refund)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **raid_card_account.lamports.borrow_mut() = 0;
+    raid_card_account.data.borrow_mut().fill(0);
+
+    msg!("Raid program left the competition");
     Ok(())
 }
 
-fn create_user_card(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+// One enrolled program's accounts while its competition is being finalized
+struct Standing<'a, 'b> {
+    raid_card_account: &'a AccountInfo<'b>,
+    raid_card: RaidCard,
+    raid_card_bytes_read: usize,
+    raid_program_account: &'a AccountInfo<'b>,
+    raid_program: RaidProgramCard,
+    raid_program_bytes_read: usize,
+    stake_account: &'a AccountInfo<'b>,
+}
+
+// Finalizing also slashes every program that abandoned the competition. Doing it here,
+// before placements and rewards exist and while the stakes are still locked, means an
+// abandoning program cannot dodge the slash by finalizing and unstaking first.
+fn finalize_competition(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let user_account = next_account_info(account_info_iter)?;
-    let user_card_account = next_account_info(account_info_iter)?;
-    let program_state_account = next_account_info(account_info_iter)?;
-    let system_program = next_account_info(account_info_iter)?;
+    let competition_account = next_account_info(account_info_iter)?
+        .card::<CompetitionCard>(program_id)?
+        .writable()?;
+    let payer_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+    let config_account = next_account_info(account_info_iter)?;
+    let token_mint_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?.token_program()?;
+    let rent = next_account_info(account_info_iter)?.rent_sysvar()?;
+    let reward_vault_account = next_account_info(account_info_iter)?.writable()?;
 
-    // Verify user is signer
-    if !user_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    let config = GlobalConfig::load(program_id, config_account)?;
 
-    // Derive PDA for user card account
-    let user_card_seed = format!("user_card_{}", user_account.key.to_string());
-    let mut hasher = Sha256::new();
-    hasher.update(user_card_seed.as_bytes());
-    let result = hasher.finalize();
-    let seed = &result[..32];
+    // Verify the token mint
+    token_mint_account.token_mint(program_id, config.token_mint_bump)?;
 
-    let (user_card_account_pda, bump) = Pubkey::find_program_address(&[seed], program_id);
+    let (mut competition_data, comp_bytes_read) =
+        CompetitionCard::custom_deserialize(&competition_account.data.borrow())?;
 
-    // Verify provided user card account matches derived PDA
-    if user_card_account.key != &user_card_account_pda {
-        msg!("Error: User card account does not match derived PDA");
-        return Err(ProgramError::InvalidAccountData);
+    // Only a competition that actually ran can be finalized, and only once
+    if competition_data.status != "active" && competition_data.status != "ended" {
+        return Err(BullPosterError::InvalidCompetitionStatus.into());
     }
 
-    // Check if the account already exists
-    if !user_card_account.data_is_empty() {
-        return Err(ProgramError::AccountAlreadyInitialized);
+    // Anyone may finalize, but not before the competition has ended
+    let current_time = Clock::get()?.unix_timestamp as u64;
+    if current_time < competition_data.end_time {
+        msg!(
+            "Competition ends at {}, current time is {}",
+            competition_data.end_time,
+            current_time
+        );
+        return Err(BullPosterError::InvalidCompetitionStatus.into());
     }
 
-    // Verify program state account
-    let (program_state_pda, _) = Pubkey::find_program_address(&[b"program_state"], program_id);
-    if program_state_account.key != &program_state_pda {
-        return Err(ProgramError::InvalidAccountData);
+    // Verify the competition's reward vault against its stored bump
+    let reward_vault_bump = competition_data.reward_vault_bump;
+    let reward_vault_seed = pda::reward_vault_seed(competition_account.key);
+    let reward_vault_pda =
+        pda::create_address(program_id, &[&reward_vault_seed], reward_vault_bump)?;
+    reward_vault_account.address(&reward_vault_pda, BullPosterError::InvalidRewardVault)?;
+
+    // Remaining accounts are (RaidCard, RaidProgramCard, stake token account) triples,
+    // one per enrolled program
+    let enrolled_programs = competition_data.enrolled_programs.clone();
+    let remaining_accounts: Vec<&AccountInfo> = account_info_iter.collect();
+    if remaining_accounts.len() != enrolled_programs.len() * 3 {
+        msg!(
+            "Expected {} raid accounts, got {}",
+            enrolled_programs.len() * 3,
+            remaining_accounts.len()
+        );
+        return Err(ProgramError::NotEnoughAccountKeys);
     }
 
-    // Create UserCard data
-    let user_card_data = UserCard {
-        user_pubkey: *user_account.key,
-        owned_programs: String::new(),
-        enrolled_programs: String::new(),
-        is_conducting_raid: false,
+    let mut standings: Vec<Standing> = Vec::with_capacity(enrolled_programs.len());
+    for triple in remaining_accounts.chunks(3) {
+        let (raid_card_account, raid_program_account, stake_account) =
+            (triple[0], triple[1], triple[2]);
+
+        raid_card_account.card::<RaidCard>(program_id)?.writable()?;
+        raid_program_account
+            .card::<RaidProgramCard>(program_id)?
+            .writable()?;
+        stake_account.writable()?;
+
+        let (raid_card, raid_card_bytes_read) =
+            RaidCard::custom_deserialize(&raid_card_account.data.borrow())?;
+        if raid_card.competition_id != *competition_account.key
+            || raid_card.raid_program_id != *raid_program_account.key
+            || !enrolled_programs.contains(raid_program_account.key)
+        {
+            return Err(BullPosterError::RaidCardMismatch.into());
+        }
+
+        // Each enrolled program must be ranked exactly once
+        if standings
+            .iter()
+            .any(|standing| standing.raid_card.raid_program_id == raid_card.raid_program_id)
+        {
+            return Err(BullPosterError::DuplicateRaidProgram.into());
+        }
+
+        // Verify the program's stake token account against its stored bump
+        let (raid_program, raid_program_bytes_read) =
+            RaidProgramCard::custom_deserialize(&raid_program_account.data.borrow())?;
+        let stake_account_pda = pda::create_address(
+            program_id,
+            &[&pda::raid_program_token_account_seed(
+                raid_program_account.key,
+            )],
+            raid_program.token_account_bump,
+        )?;
+        stake_account.address(&stake_account_pda, BullPosterError::InvalidStakeAccount)?;
+
+        standings.push(Standing {
+            raid_card_account,
+            raid_card,
+            raid_card_bytes_read,
+            raid_program_account,
+            raid_program,
+            raid_program_bytes_read,
+            stake_account,
+        });
+    }
+
+    // Rank by burn total, breaking ties by enrollment order so that the caller's account
+    // order cannot decide a tie
+    let enrollment_index = |raid_card: &RaidCard| {
+        enrolled_programs
+            .iter()
+            .position(|program| *program == raid_card.raid_program_id)
+    };
+    standings.sort_by(|a, b| {
+        b.raid_card
+            .total_burned
+            .cmp(&a.raid_card.total_burned)
+            .then_with(|| enrollment_index(&a.raid_card).cmp(&enrollment_index(&b.raid_card)))
+    });
+
+    // A raid that joined and then recorded no burns at all abandoned the competition. A
+    // challenger that forfeits its own PvP pays the opponent; everyone else pays the
+    // reward pool. Every share is taken from the stake as it was before any slash.
+    let mut slashes: Vec<(usize, Option<usize>, u64)> = Vec::new();
+    for (index, standing) in standings.iter().enumerate() {
+        if standing.raid_card.total_burned > 0 || standing.raid_card.is_slashed {
+            continue;
+        }
+
+        let forfeited_pvp = competition_data.competition_type == "PvP"
+            && competition_data.challenger_program_id == Some(*standing.raid_program_account.key);
+        let opponent = if forfeited_pvp {
+            let opponent_index = standings
+                .iter()
+                .position(|other| {
+                    Some(*other.raid_program_account.key) == competition_data.challenged_program_id
+                })
+                .ok_or(BullPosterError::InvalidOpponent)?;
+            Some(opponent_index)
+        } else {
+            None
+        };
+
+        let staked_amount =
+            StateWithExtensions::<Account>::unpack(&standing.stake_account.data.borrow())?
+                .base
+                .amount;
+        let slash_amount =
+            (staked_amount as u128 * config.slash_basis_points as u128 / 10_000) as u64;
+        slashes.push((index, opponent, slash_amount));
+    }
+
+    let pays_reward_pool = slashes
+        .iter()
+        .any(|(_, opponent, slash_amount)| opponent.is_none() && *slash_amount > 0);
+    if pays_reward_pool && reward_vault_account.data_is_empty() {
+        msg!("Creating reward vault token account...");
+        create_pda_token_account(
+            payer_account,
+            reward_vault_account,
+            token_mint_account,
+            token_program,
+            system_program,
+            rent,
+            &[&reward_vault_seed, &[reward_vault_bump]],
+        )?;
+    }
+
+    for (index, opponent, slash_amount) in slashes.iter() {
+        let standing = &standings[*index];
+        let destination = match opponent {
+            Some(opponent_index) => standings[*opponent_index].stake_account,
+            None => reward_vault_account,
+        };
+
+        if *slash_amount > 0 {
+            msg!(
+                "Slashing {} staked tokens from {}",
+                slash_amount,
+                standing.raid_program_account.key
+            );
+            let stake_account_seed =
+                pda::raid_program_token_account_seed(standing.raid_program_account.key);
+            invoke_signed(
+                &token_instruction::transfer_checked(
+                    token_program.key,
+                    standing.stake_account.key,
+                    token_mint_account.key,
+                    destination.key,
+                    standing.stake_account.key,
+                    &[],
+                    *slash_amount,
+                    9,
+                )?,
+                &[
+                    standing.stake_account.clone(),
+                    token_mint_account.clone(),
+                    destination.clone(),
+                    standing.stake_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[
+                    &stake_account_seed,
+                    &[standing.raid_program.token_account_bump],
+                ]],
+            )?;
+        }
+    }
+
+    competition_data.placements = standings
+        .iter()
+        .map(|standing| standing.raid_card.raid_id)
+        .collect();
+    competition_data.status = "finalized".to_string();
+
+    ensure_account_space(
+        competition_account,
+        payer_account,
+        system_program,
+        competition_data.stored_len()?,
+    )?;
+    let mut data = competition_account.data.borrow_mut();
+    let bytes_written = competition_data.custom_serialize(&mut data)?;
+    if bytes_written < comp_bytes_read {
+        data[bytes_written..comp_bytes_read].fill(0);
+    }
+    drop(data);
+
+    // Update each program's record; the first placement wins if anything was burned at all
+    for (placement, standing) in standings.iter_mut().enumerate() {
+        let slashed = slashes.iter().any(|(index, _, _)| *index == placement);
+        let raid_program_data = &mut standing.raid_program;
+
+        raid_program_data.total_raids_partaken += 1;
+        if placement == 0 && standing.raid_card.total_burned > 0 {
+            raid_program_data.total_raid_wins += 1;
+        }
+        if raid_program_data.active_raid_id == standing.raid_card.raid_id {
+            raid_program_data.is_conducting_raid = false;
+            raid_program_data.active_raid_id = Pubkey::default();
+        }
+
+        // Slashed programs and PvP opponents that received a slash hold a new stake
+        if slashed
+            || slashes
+                .iter()
+                .any(|(_, opponent, _)| *opponent == Some(placement))
+        {
+            raid_program_data.staked_amount =
+                StateWithExtensions::<Account>::unpack(&standing.stake_account.data.borrow())?
+                    .base
+                    .amount;
+        }
+        if slashed {
+            if raid_program_data.staked_amount < config.required_stake_amount {
+                msg!("Stake below requirement, deactivating raid program");
+                raid_program_data.is_deactivated = true;
+            }
+
+            standing.raid_card.is_slashed = true;
+            let mut data = standing.raid_card_account.data.borrow_mut();
+            let bytes_written = standing.raid_card.custom_serialize(&mut data)?;
+            if bytes_written < standing.raid_card_bytes_read {
+                data[bytes_written..standing.raid_card_bytes_read].fill(0);
+            }
+        }
+
+        let mut data = standing.raid_program_account.data.borrow_mut();
+        let bytes_written = raid_program_data.custom_serialize(&mut data)?;
+        if bytes_written < standing.raid_program_bytes_read {
+            data[bytes_written..standing.raid_program_bytes_read].fill(0);
+        }
+    }
+
+    msg!(
+        "Competition finalized with {} ranked raids, {} slashed",
+        standings.len(),
+        slashes.len()
+    );
+    Ok(())
+}
+
+fn check_raid_status(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let competition_account = next_account_info(account_info_iter)?
+        .card::<CompetitionCard>(program_id)?
+        .writable()?;
+
+    let (mut competition_data, comp_bytes_read) =
+        CompetitionCard::custom_deserialize(&competition_account.data.borrow())?;
+
+    let previous_status = competition_data.status.clone();
+    if !competition_data.advance_status(Clock::get()?.unix_timestamp as u64) {
+        msg!("Competition status unchanged: {}", previous_status);
+        return Ok(());
+    }
+
+    let mut data = competition_account.data.borrow_mut();
+    let bytes_written = competition_data.custom_serialize(&mut data)?;
+    if bytes_written < comp_bytes_read {
+        data[bytes_written..comp_bytes_read].fill(0);
+    }
+
+    msg!(
+        "Competition status moved from {} to {}",
+        previous_status,
+        competition_data.status
+    );
+    Ok(())
+}
+
+// Grow a program account so that `required_len` bytes fit, with the payer topping
+// up rent. Accounts grow in ACCOUNT_GROWTH_STEP steps so that appending to a list
+// does not realloc every time.
+fn ensure_account_space<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    required_len: usize,
+) -> ProgramResult {
+    let current_len = account.data_len();
+    if required_len <= current_len {
+        return Ok(());
+    }
+
+    let new_len =
+        (required_len + ACCOUNT_GROWTH_STEP - 1) / ACCOUNT_GROWTH_STEP * ACCOUNT_GROWTH_STEP;
+    let new_len = new_len.min(current_len + MAX_PERMITTED_DATA_INCREASE);
+    if new_len < required_len {
+        msg!(
+            "Account {} cannot grow from {} to {} bytes in one instruction",
+            account.key,
+            current_len,
+            required_len
+        );
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    let lamports_needed = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if lamports_needed > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, lamports_needed),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+
+    account.realloc(new_len, true)?;
+    msg!(
+        "Resized account {} from {} to {} bytes",
+        account.key,
+        current_len,
+        new_len
+    );
+    Ok(())
+}
+
+// Maintenance instruction to grow an existing account ahead of the handlers that
+// append to it. Accounts only grow, so anyone willing to pay the rent may call it.
+fn resize_account(program_id: &Pubkey, accounts: &[AccountInfo], new_len: usize) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let target_account = next_account_info(account_info_iter)?
+        .program_owned(program_id)?
+        .writable()?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+
+    if new_len <= target_account.data_len() {
+        msg!("Account is already {} bytes", target_account.data_len());
+        return Err(BullPosterError::AccountAlreadyLargeEnough.into());
+    }
+
+    ensure_account_space(target_account, payer_account, system_program, new_len)
+}
+
+// Append an entry to the registry identified by `seed`. `head` is the registry's
+// counter in ProgramStateCard; it selects the page to write and is advanced here,
+// so the caller must persist ProgramStateCard afterwards. The first write to a page
+// creates it, with the payer funding the rent.
+fn append_to_registry<'a>(
+    program_id: &Pubkey,
+    seed: &[u8],
+    page_account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    head: &mut u64,
+    entry: Pubkey,
+) -> ProgramResult {
+    let page_index = *head / REGISTRY_PAGE_CAPACITY;
+    let page_index_bytes = page_index.to_le_bytes();
+
+    let mut page = if page_account.data_is_empty() {
+        let (page_pda, bump) = pda::registry_page_address(program_id, seed, page_index);
+        if page_account.key != &page_pda {
+            msg!("Expected registry page {} at {}", page_index, page_pda);
+            return Err(BullPosterError::InvalidRegistryPage.into());
+        }
+
+        create_pda_account(
+            payer,
+            &Rent::get()?,
+            1000,
+            program_id,
+            system_program,
+            page_account,
+            &[seed, &page_index_bytes, &[bump]],
+        )?;
+        RegistryPageCard {
+            page_index,
+            entries: Vec::new(),
+            bump,
+        }
+    } else {
+        page_account.card::<RegistryPageCard>(program_id)?;
+        let page = RegistryPageCard::read_card(&page_account.data.borrow())?;
+
+        // Existing pages are checked against their stored bump
+        let page_pda = pda::create_address(program_id, &[seed, &page_index_bytes], page.bump)?;
+        page_account.address(&page_pda, BullPosterError::InvalidRegistryPage)?;
+        page
+    };
+
+    page.entries.push(entry);
+    page.write_card(&mut page_account.data.borrow_mut())?;
+    *head += 1;
+
+    msg!("Registered {} on registry page {}", entry, page_index);
+    Ok(())
+}
+
+// Bring a card written with an older layout up to the current one. Accounts that
+// are already current are left alone, so migrating twice is harmless.
+fn migrate_card<'a, T: Card>(
+    program_id: &Pubkey,
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let version = account.data.borrow()[8];
+    if version == T::VERSION {
+        msg!(
+            "{} is already at version {}",
+            std::any::type_name::<T>(),
+            version
+        );
+        return Ok(());
+    }
+    if version > T::VERSION {
+        msg!(
+            "{} version {} is newer than this program supports",
+            std::any::type_name::<T>(),
+            version
+        );
+        return Err(BullPosterError::UnsupportedAccountVersion.into());
+    }
+
+    let card = T::upgrade(
+        program_id,
+        account.key,
+        version,
+        &account.data.borrow()[CARD_HEADER_LEN..],
+    )?;
+    store_migrated_card(account, payer, system_program, &card, version)
+}
+
+// Convert a headerless account from its legacy layout; the caller has worked out
+// which card type it holds
+fn migrate_legacy_card<'a, T: Card>(
+    program_id: &Pubkey,
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let card = T::upgrade(
+        program_id,
+        account.key,
+        LEGACY_VERSION,
+        &account.data.borrow(),
+    )?;
+    store_migrated_card(account, payer, system_program, &card, LEGACY_VERSION)
+}
+
+// Overwrite the account with `card` in the current layout, growing it first if needed
+fn store_migrated_card<'a, T: Card>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    card: &T,
+    from_version: u8,
+) -> ProgramResult {
+    ensure_account_space(account, payer, system_program, card.stored_len()?)?;
+    let mut data = account.data.borrow_mut();
+    let bytes_written = card.pack_into(&mut data)?;
+    data[bytes_written..].fill(0);
+
+    msg!(
+        "Migrated {} from version {} to {}",
+        std::any::type_name::<T>(),
+        from_version,
+        T::VERSION
+    );
+    Ok(())
+}
+
+// Legacy accounts have no discriminator, so their type is told from their address.
+// The singletons sit at fixed seeds, user cards and raid cards at seeds built from
+// their leading pubkeys, and raid programs at a seed built from their owner and
+// name. Competitions cannot be re-derived, but each one stored its own address
+// first.
+fn migrate_legacy_account<'a, 'b>(
+    program_id: &Pubkey,
+    account: &'b AccountInfo<'a>,
+    payer: &'b AccountInfo<'a>,
+    system_program: &'b AccountInfo<'a>,
+    account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+) -> ProgramResult {
+    if account.key == &pda::program_state_address(program_id).0 {
+        return migrate_legacy_program_state(
+            program_id,
+            account,
+            payer,
+            system_program,
+            account_info_iter,
+        );
+    }
+    if account.key == &pda::leaderboard_address(program_id).0 {
+        return migrate_legacy_card::<ProgramLeaderboardStateCard>(
+            program_id,
+            account,
+            payer,
+            system_program,
+        );
+    }
+
+    let (first_key, second_key) = {
+        let data = account.data.borrow();
+        if data.len() < 64 {
+            return Err(BullPosterError::InvalidAccountDiscriminator.into());
+        }
+        (
+            Pubkey::new_from_array(data[..32].try_into().unwrap()),
+            Pubkey::new_from_array(data[32..64].try_into().unwrap()),
+        )
+    };
+    let is_raid_program = || {
+        legacy::read::<legacy::RaidProgramCard>(&account.data.borrow()).is_ok_and(|card| {
+            account.key == &pda::raid_program_address(program_id, &card.user_key, &card.name).0
+        })
+    };
+
+    if account.key == &pda::user_card_address(program_id, &first_key).0 {
+        migrate_legacy_card::<UserCard>(program_id, account, payer, system_program)
+    } else if account.key == &pda::raid_card_address(program_id, &first_key, &second_key).0 {
+        migrate_legacy_card::<RaidCard>(program_id, account, payer, system_program)
+    } else if is_raid_program() {
+        migrate_legacy_card::<RaidProgramCard>(program_id, account, payer, system_program)
+    } else if account.key == &first_key {
+        migrate_legacy_card::<CompetitionCard>(program_id, account, payer, system_program)
+    } else {
+        msg!("Account {} is not a recognized legacy card", account.key);
+        Err(BullPosterError::InvalidAccountDiscriminator.into())
+    }
+}
+
+// The legacy program state is migrated together with the three legacy registries,
+// whose entries move onto the paged registries so that the new heads count them.
+// After the system program come each legacy registry and page 0 of the registry
+// replacing it: raid programs, then raids, then competitions. A legacy registry
+// held at most 22 pubkeys in its 1000 bytes, so page 0 takes all of them.
+fn migrate_legacy_program_state<'a, 'b>(
+    program_id: &Pubkey,
+    account: &'b AccountInfo<'a>,
+    payer: &'b AccountInfo<'a>,
+    system_program: &'b AccountInfo<'a>,
+    account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+) -> ProgramResult {
+    let mut program_state = ProgramStateCard::upgrade(
+        program_id,
+        account.key,
+        LEGACY_VERSION,
+        &account.data.borrow(),
+    )?;
+
+    for (legacy_seed, seed, head) in [
+        (
+            legacy::RAID_PROGRAMS_STATE_SEED,
+            pda::RAID_PROGRAMS_REGISTRY_SEED,
+            &mut program_state.registered_programs_count,
+        ),
+        (
+            legacy::RAIDS_STATE_SEED,
+            pda::RAIDS_REGISTRY_SEED,
+            &mut program_state.registered_raids_count,
+        ),
+        (
+            legacy::COMPETITIONS_STATE_SEED,
+            pda::COMPETITIONS_REGISTRY_SEED,
+            &mut program_state.registered_competitions_count,
+        ),
+    ] {
+        let legacy_registry_pda = Pubkey::find_program_address(&[legacy_seed], program_id).0;
+        let legacy_registry_account = next_account_info(account_info_iter)?
+            .program_owned(program_id)?
+            .address(&legacy_registry_pda, BullPosterError::InvalidRegistryPage)?;
+        let page_account = next_account_info(account_info_iter)?.writable()?;
+
+        let legacy_registry: legacy::RegistryCard =
+            legacy::read(&legacy_registry_account.data.borrow())?;
+        for entry in legacy::parse_pubkey_list(&legacy_registry.pubkeys)? {
+            append_to_registry(
+                program_id,
+                seed,
+                page_account,
+                payer,
+                system_program,
+                head,
+                entry,
+            )?;
+        }
+    }
+
+    store_migrated_card(
+        account,
+        payer,
+        system_program,
+        &program_state,
+        LEGACY_VERSION,
+    )
+}
+
+// Permissionless instruction that upgrades any program account to the current
+// layout of its card type, picked by the account's discriminator, or by its
+// address for legacy accounts written before cards had one
+fn migrate_account(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let target_account = next_account_info(account_info_iter)?
+        .program_owned(program_id)?
+        .writable()?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+
+    if target_account.data_len() < CARD_HEADER_LEN {
+        return Err(BullPosterError::InvalidAccountDiscriminator.into());
+    }
+    let discriminator: [u8; 8] = target_account.data.borrow()[..8].try_into().unwrap();
+
+    match discriminator {
+        d if d == GlobalConfig::DISCRIMINATOR => {
+            migrate_card::<GlobalConfig>(program_id, target_account, payer_account, system_program)
+        }
+        d if d == ProgramStateCard::DISCRIMINATOR => migrate_card::<ProgramStateCard>(
+            program_id,
+            target_account,
+            payer_account,
+            system_program,
+        ),
+        d if d == RegistryPageCard::DISCRIMINATOR => migrate_card::<RegistryPageCard>(
+            program_id,
+            target_account,
+            payer_account,
+            system_program,
+        ),
+        d if d == BurnLedger::DISCRIMINATOR => {
+            migrate_card::<BurnLedger>(program_id, target_account, payer_account, system_program)
+        }
+        d if d == RaidCard::DISCRIMINATOR => {
+            migrate_card::<RaidCard>(program_id, target_account, payer_account, system_program)
+        }
+        d if d == CompetitionCard::DISCRIMINATOR => migrate_card::<CompetitionCard>(
+            program_id,
+            target_account,
+            payer_account,
+            system_program,
+        ),
+        d if d == RaidProgramCard::DISCRIMINATOR => migrate_card::<RaidProgramCard>(
+            program_id,
+            target_account,
+            payer_account,
+            system_program,
+        ),
+        d if d == UserCard::DISCRIMINATOR => {
+            migrate_card::<UserCard>(program_id, target_account, payer_account, system_program)
+        }
+        d if d == MerkleDistributorCard::DISCRIMINATOR => migrate_card::<MerkleDistributorCard>(
+            program_id,
+            target_account,
+            payer_account,
+            system_program,
+        ),
+        d if d == ProgramLeaderboardStateCard::DISCRIMINATOR => {
+            migrate_card::<ProgramLeaderboardStateCard>(
+                program_id,
+                target_account,
+                payer_account,
+                system_program,
+            )
+        }
+        _ => migrate_legacy_account(
+            program_id,
+            target_account,
+            payer_account,
+            system_program,
+            account_info_iter,
+        ),
+    }
+}
+
+// Create a token account at a PDA that is its own authority, signing with its seeds
+fn create_pda_token_account<'a>(
+    payer: &AccountInfo<'a>,
+    token_account: &AccountInfo<'a>,
+    token_mint_account: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    create_pda_account(
+        payer,
+        &Rent::get()?,
+        Account::LEN,
+        token_program.key,
+        system_program,
+        token_account,
+        signer_seeds,
+    )?;
+
+    invoke_signed(
+        &token_instruction::initialize_account3(
+            token_program.key,
+            token_account.key,
+            token_mint_account.key,
+            token_account.key,
+        )?,
+        &[
+            token_account.clone(),
+            token_mint_account.clone(),
+            rent.clone(),
+        ],
+        &[signer_seeds],
+    )
+}
+
+fn fund_reward_vault(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    mint: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let funder_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let competition_account =
+        next_account_info(account_info_iter)?.card::<CompetitionCard>(program_id)?;
+    let reward_vault_account = next_account_info(account_info_iter)?.writable()?;
+    let token_mint_account = next_account_info(account_info_iter)?.writable()?;
+    let funder_token_account = next_account_info(account_info_iter)?.writable()?;
+    let token_program = next_account_info(account_info_iter)?.token_program()?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+    let rent = next_account_info(account_info_iter)?.rent_sysvar()?;
+    let config_account = next_account_info(account_info_iter)?.writable()?;
+
+    let mut config = GlobalConfig::load(program_id, config_account)?;
+
+    // Minting new rewards is reserved for the authority
+    if mint && funder_account.key != &config.authority {
+        return Err(BullPosterError::Unauthorized.into());
+    }
+
+    // Rewards can no longer be added once they have been paid out
+    let (competition_data, _) =
+        CompetitionCard::custom_deserialize(&competition_account.data.borrow())?;
+    if !competition_data.distributed_rewards.is_empty() {
+        return Err(BullPosterError::InvalidCompetitionStatus.into());
+    }
+
+    // Verify the token mint
+    let mint_bump = config.token_mint_bump;
+    token_mint_account.token_mint(program_id, mint_bump)?;
+
+    // Verify the competition's reward vault against its stored bump
+    let reward_vault_bump = competition_data.reward_vault_bump;
+    let reward_vault_seed = pda::reward_vault_seed(competition_account.key);
+    let reward_vault_pda =
+        pda::create_address(program_id, &[&reward_vault_seed], reward_vault_bump)?;
+    if reward_vault_account.key != &reward_vault_pda {
+        return Err(BullPosterError::InvalidRewardVault.into());
+    }
+
+    // Create the vault on first funding; it is its own authority
+    if reward_vault_account.data_is_empty() {
+        msg!("Creating reward vault token account...");
+        create_pda_token_account(
+            funder_account,
+            reward_vault_account,
+            token_mint_account,
+            token_program,
+            system_program,
+            rent,
+            &[&reward_vault_seed, &[reward_vault_bump]],
+        )?;
+    }
+
+    if mint {
+        // Minted rewards may not push the vault past the competition's reward cap
+        let vault_balance =
+            StateWithExtensions::<Account>::unpack(&reward_vault_account.data.borrow())?
+                .base
+                .amount;
+        // Competitions created before caps were stored, which MigrateAccount gives a cap
+        // of zero, cannot be minted into
+        let reward_cap = competition_data.reward_cap;
+        if vault_balance.saturating_add(amount) > reward_cap {
+            msg!(
+                "Minting {} would exceed the reward cap of {} for {} competitions",
+                amount,
+                reward_cap,
+                competition_data.competition_type
+            );
+            return Err(BullPosterError::EmissionLimitExceeded.into());
+        }
+
+        config.record_mint(amount, Clock::get()?.unix_timestamp as u64)?;
+        config.write_card(&mut config_account.data.borrow_mut())?;
+
+        msg!("Minting {} tokens into reward vault", amount);
+        invoke_signed(
+            &token_instruction::mint_to(
+                token_program.key,
+                token_mint_account.key,
+                reward_vault_account.key,
+                token_mint_account.key,
+                &[],
+                amount,
+            )?,
+            &[
+                token_mint_account.clone(),
+                reward_vault_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[pda::TOKEN_MINT_SEED, &[mint_bump]]],
+        )?;
+    } else {
+        msg!("Transferring {} tokens into reward vault", amount);
+        invoke(
+            &token_instruction::transfer_checked(
+                token_program.key,
+                funder_token_account.key,
+                token_mint_account.key,
+                reward_vault_account.key,
+                funder_account.key,
+                &[],
+                amount,
+                9,
+            )?,
+            &[
+                funder_token_account.clone(),
+                token_mint_account.clone(),
+                reward_vault_account.clone(),
+                funder_account.clone(),
+                token_program.clone(),
+            ],
+        )?;
+    }
+
+    msg!("Reward vault funded successfully");
+    Ok(())
+}
+
+// Linear placement weights over the raids that earn rewards: with n of them, the best
+// placed weighs n and the worst weighs 1. The other raids get nothing. None when no
+// raid earns rewards.
+fn placement_shares(reward_pool: u64, earns_rewards: &[bool]) -> Option<Vec<u64>> {
+    let winner = earns_rewards.iter().position(|earns| *earns)?;
+    let raid_count = earns_rewards.iter().filter(|earns| **earns).count() as u128;
+    let total_weight = raid_count * (raid_count + 1) / 2;
+
+    let mut weight = raid_count + 1;
+    let mut shares: Vec<u64> = earns_rewards
+        .iter()
+        .map(|earns| {
+            if !*earns {
+                return 0;
+            }
+            weight -= 1;
+            (reward_pool as u128 * weight / total_weight) as u64
+        })
+        .collect();
+    // Rounding dust goes to the winner
+    let dust = reward_pool - shares.iter().sum::<u64>();
+    shares[winner] += dust;
+    Some(shares)
+}
+
+fn distribute_rewards(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let competition_account = next_account_info(account_info_iter)?
+        .card::<CompetitionCard>(program_id)?
+        .writable()?;
+    let reward_vault_account = next_account_info(account_info_iter)?.writable()?;
+    let claim_vault_account = next_account_info(account_info_iter)?.writable()?;
+    let token_mint_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?.token_program()?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+    let rent = next_account_info(account_info_iter)?.rent_sysvar()?;
+    // Also read for the pause check, so it has to come before the raid account pairs
+    let config_account = next_account_info(account_info_iter)?;
+
+    let config = GlobalConfig::load(program_id, config_account)?;
+
+    let (mut competition_data, comp_bytes_read) =
+        CompetitionCard::custom_deserialize(&competition_account.data.borrow())?;
+
+    // Rewards follow the placements, so the competition must be finalized and not yet paid
+    if competition_data.status != "finalized" || !competition_data.distributed_rewards.is_empty() {
+        return Err(BullPosterError::InvalidCompetitionStatus.into());
+    }
+
+    let placements = competition_data.placements.clone();
+
+    // Verify the token mint
+    token_mint_account.token_mint(program_id, config.token_mint_bump)?;
+
+    // Verify the competition's reward vault against its stored bump
+    let reward_vault_bump = competition_data.reward_vault_bump;
+    let reward_vault_seed = pda::reward_vault_seed(competition_account.key);
+    let reward_vault_pda =
+        pda::create_address(program_id, &[&reward_vault_seed], reward_vault_bump)?;
+    if reward_vault_account.key != &reward_vault_pda {
+        return Err(BullPosterError::InvalidRewardVault.into());
+    }
+
+    // Verify the program-wide vault that user claims are paid from
+    let claim_vault_bump = config.claim_vault_bump;
+    let claim_vault_pda =
+        pda::create_address(program_id, &[pda::CLAIM_VAULT_SEED], claim_vault_bump)?;
+    if claim_vault_account.key != &claim_vault_pda {
+        return Err(BullPosterError::InvalidClaimVault.into());
+    }
+
+    // Create the claim vault the first time any competition distributes; it is its own authority
+    if claim_vault_account.data_is_empty() {
+        msg!("Creating reward claim vault token account...");
+        create_pda_token_account(
+            payer_account,
+            claim_vault_account,
+            token_mint_account,
+            token_program,
+            system_program,
+            rent,
+            &[pda::CLAIM_VAULT_SEED, &[claim_vault_bump]],
+        )?;
+    }
+
+    let reward_pool = StateWithExtensions::<Account>::unpack(&reward_vault_account.data.borrow())?
+        .base
+        .amount;
+    if reward_pool == 0 {
+        return Err(BullPosterError::InsufficientTokens.into());
+    }
+
+    // Remaining accounts are (RaidCard, RaidProgramCard) pairs in placement order
+    let remaining_accounts: Vec<&AccountInfo> = account_info_iter.collect();
+    if remaining_accounts.len() != placements.len() * 2 {
+        msg!(
+            "Expected {} raid accounts, got {}",
+            placements.len() * 2,
+            remaining_accounts.len()
+        );
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let mut raid_cards: Vec<(RaidCard, usize)> = Vec::with_capacity(placements.len());
+    for (raid_card_key, pair) in placements.iter().zip(remaining_accounts.chunks(2)) {
+        let (raid_card_account, raid_program_account) = (pair[0], pair[1]);
+
+        raid_card_account.card::<RaidCard>(program_id)?.writable()?;
+        raid_program_account
+            .card::<RaidProgramCard>(program_id)?
+            .writable()?;
+        if raid_card_account.key != raid_card_key {
+            return Err(BullPosterError::InvalidRaidCardAccount.into());
+        }
+
+        let (raid_card_data, raid_card_bytes_read) =
+            RaidCard::custom_deserialize(&raid_card_account.data.borrow())?;
+        if raid_card_data.competition_id != *competition_account.key
+            || raid_card_data.raid_program_id != *raid_program_account.key
+        {
+            return Err(BullPosterError::RaidCardMismatch.into());
+        }
+        raid_cards.push((raid_card_data, raid_card_bytes_read));
+    }
+
+    // Only raids that can credit their share are weighted, so the whole pool reaches users
+    let earns_rewards: Vec<bool> = raid_cards
+        .iter()
+        .map(|(raid_card, _)| raid_card.earns_rewards())
+        .collect();
+    let shares = placement_shares(reward_pool, &earns_rewards).ok_or_else(|| {
+        msg!("No raid burned tokens without being slashed");
+        ProgramError::from(BullPosterError::NoEngagement)
+    })?;
+
+    // Move the whole pool into the claim vault; participants claim their part of each raid's share
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            token_program.key,
+            reward_vault_account.key,
+            token_mint_account.key,
+            claim_vault_account.key,
+            &reward_vault_pda,
+            &[],
+            reward_pool,
+            9,
+        )?,
+        &[
+            reward_vault_account.clone(),
+            token_mint_account.clone(),
+            claim_vault_account.clone(),
+            reward_vault_account.clone(),
+            token_program.clone(),
+        ],
+        &[&[&reward_vault_seed, &[reward_vault_bump]]],
+    )?;
+
+    let mut competition_rewards: Vec<(Pubkey, u64)> = Vec::with_capacity(placements.len());
+    for (((raid_card_key, pair), (mut raid_card_data, raid_card_bytes_read)), share) in placements
+        .iter()
+        .zip(remaining_accounts.chunks(2))
+        .zip(raid_cards)
+        .zip(shares)
+    {
+        let (raid_card_account, raid_program_account) = (pair[0], pair[1]);
+
+        raid_card_data.reward_amount = share;
+        let mut data = raid_card_account.data.borrow_mut();
+        let bytes_written = raid_card_data.custom_serialize(&mut data)?;
+        if bytes_written < raid_card_bytes_read {
+            data[bytes_written..raid_card_bytes_read].fill(0);
+        }
+        drop(data);
+
+        let (mut raid_program_data, raid_prog_bytes_read) =
+            RaidProgramCard::custom_deserialize(&raid_program_account.data.borrow())?;
+        raid_program_data.total_rewards_distributed += share;
+        let mut data = raid_program_account.data.borrow_mut();
+        let bytes_written = raid_program_data.custom_serialize(&mut data)?;
+        if bytes_written < raid_prog_bytes_read {
+            data[bytes_written..raid_prog_bytes_read].fill(0);
+        }
+        drop(data);
+
+        competition_rewards.push((*raid_card_key, share));
+    }
+
+    competition_data.distributed_rewards = competition_rewards;
+    competition_data.total_rewards_distributed += reward_pool;
+
+    ensure_account_space(
+        competition_account,
+        payer_account,
+        system_program,
+        competition_data.stored_len()?,
+    )?;
+    let mut data = competition_account.data.borrow_mut();
+    let bytes_written = competition_data.custom_serialize(&mut data)?;
+    if bytes_written < comp_bytes_read {
+        data[bytes_written..comp_bytes_read].fill(0);
+    }
+
+    msg!(
+        "Distributed {} tokens across {} raids",
+        reward_pool,
+        placements.len()
+    );
+    Ok(())
+}
+
+fn credit_raid_rewards(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    engagement_scores: Vec<u64>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let raid_card_account = next_account_info(account_info_iter)?
+        .card::<RaidCard>(program_id)?
+        .writable()?;
+    let config_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+
+    let config = GlobalConfig::load(program_id, config_account)?;
+
+    // Verify the authority
+    if authority_account.key != &config.authority {
+        return Err(BullPosterError::Unauthorized.into());
+    }
+
+    let (mut raid_card_data, raid_card_bytes_read) =
+        RaidCard::custom_deserialize(&raid_card_account.data.borrow())?;
+
+    // A raid is credited once, after its competition has distributed rewards to it
+    if raid_card_data.reward_amount == 0 || !raid_card_data.placements.is_empty() {
+        return Err(BullPosterError::InvalidCompetitionStatus.into());
+    }
+
+    // Remaining accounts are (UserCard, BurnLedger) pairs, one per engagement score. The
+    // ledger proves the user burned for this raid and weighs their share by those burns.
+    let participant_accounts: Vec<&AccountInfo> = account_info_iter.collect();
+    if participant_accounts.is_empty() || participant_accounts.len() != engagement_scores.len() * 2
+    {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let total_engagement: u128 = engagement_scores.iter().map(|score| *score as u128).sum();
+    if total_engagement == 0 {
+        msg!("No engagement, no rewards to credit");
+        return Err(BullPosterError::NoEngagement.into());
+    }
+
+    let mut participants: Vec<(&AccountInfo, UserCard, usize, u64)> =
+        Vec::with_capacity(engagement_scores.len());
+    for pair in participant_accounts.chunks(2) {
+        let user_card_account = pair[0].card::<UserCard>(program_id)?.writable()?;
+        let burn_ledger_account = pair[1].card::<BurnLedger>(program_id)?;
+
+        let (user_card_data, user_card_bytes_read) =
+            UserCard::custom_deserialize(&user_card_account.data.borrow())?;
+
+        // Only users enrolled in the raiding program take part in its rewards
+        if !user_card_data
+            .enrolled_programs
+            .contains(&raid_card_data.raid_program_id)
+        {
+            return Err(BullPosterError::UserNotEnrolled.into());
+        }
+
+        let user_key = user_card_data.user_pubkey;
+        if participants
+            .iter()
+            .any(|(_, user_card, _, _)| user_card.user_pubkey == user_key)
+        {
+            return Err(BullPosterError::DuplicateUser.into());
+        }
+
+        // The ledger must be this user's ledger for this raid
+        let burn_ledger = BurnLedger::read_card(&burn_ledger_account.data.borrow())?;
+        let burn_ledger_pda = pda::create_address(
+            program_id,
+            &[&pda::burn_ledger_seed(&user_key, raid_card_account.key)],
+            burn_ledger.bump,
+        )?;
+        burn_ledger_account.address(&burn_ledger_pda, BullPosterError::InvalidBurnLedgerAccount)?;
+        if burn_ledger.user_id != user_key || burn_ledger.raid_id != *raid_card_account.key {
+            return Err(BullPosterError::InvalidBurnLedgerAccount.into());
+        }
+
+        participants.push((
+            user_card_account,
+            user_card_data,
+            user_card_bytes_read,
+            burn_ledger.total_burned,
+        ));
+    }
+
+    // Half the reward is split by engagement, as the backend does, and half by tokens
+    // burned. Every ledger holds at least one burn, so the burn total is never zero.
+    let total_burned: u128 = participants
+        .iter()
+        .map(|(_, _, _, burned)| *burned as u128)
+        .sum();
+    let engagement_pool = raid_card_data.reward_amount as u128 / 2;
+    let burn_pool = raid_card_data.reward_amount as u128 - engagement_pool;
+    let mut shares: Vec<u64> = engagement_scores
+        .iter()
+        .zip(participants.iter())
+        .map(|(score, (_, _, _, burned))| {
+            (engagement_pool * *score as u128 / total_engagement
+                + burn_pool * *burned as u128 / total_burned) as u64
+        })
+        .collect();
+
+    // Rounding dust goes to the largest share
+    let dust = raid_card_data.reward_amount - shares.iter().sum::<u64>();
+    let mut ranking: Vec<usize> = (0..shares.len()).collect();
+    ranking.sort_by(|a, b| shares[*b].cmp(&shares[*a]));
+    shares[ranking[0]] += dust;
+
+    let mut user_rewards: Vec<(Pubkey, u64)> = Vec::with_capacity(shares.len());
+    for ((user_card_account, mut user_card_data, user_card_bytes_read, _), share) in
+        participants.into_iter().zip(shares.iter())
+    {
+        user_card_data.claimable_rewards = user_card_data
+            .claimable_rewards
+            .checked_add(*share)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        user_card_data.participated_raids += 1;
+
+        let mut data = user_card_account.data.borrow_mut();
+        let bytes_written = user_card_data.custom_serialize(&mut data)?;
+        if bytes_written < user_card_bytes_read {
+            data[bytes_written..user_card_bytes_read].fill(0);
+        }
+
+        user_rewards.push((user_card_data.user_pubkey, *share));
+    }
+
+    raid_card_data.placements = ranking.iter().map(|index| user_rewards[*index].0).collect();
+    raid_card_data.distributed_rewards = user_rewards;
+
+    ensure_account_space(
+        raid_card_account,
+        authority_account,
+        system_program,
+        raid_card_data.stored_len()?,
+    )?;
+    let mut data = raid_card_account.data.borrow_mut();
+    let bytes_written = raid_card_data.custom_serialize(&mut data)?;
+    if bytes_written < raid_card_bytes_read {
+        data[bytes_written..raid_card_bytes_read].fill(0);
+    }
+
+    msg!(
+        "Credited {} tokens to {} participants",
+        raid_card_data.reward_amount,
+        engagement_scores.len()
+    );
+    Ok(())
+}
+
+fn claim_rewards(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let user_account = next_account_info(account_info_iter)?.signer()?;
+    let user_card_account = next_account_info(account_info_iter)?
+        .card::<UserCard>(program_id)?
+        .writable()?;
+    let claim_vault_account = next_account_info(account_info_iter)?.writable()?;
+    let user_token_account = next_account_info(account_info_iter)?.writable()?;
+    let token_mint_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?.token_program()?;
+    let config_account = next_account_info(account_info_iter)?;
+
+    let config = GlobalConfig::load(program_id, config_account)?;
+
+    // Verify the user card account belongs to the signer
+    let (mut user_card_data, user_card_bytes_read) =
+        UserCard::custom_deserialize(&user_card_account.data.borrow())?;
+    if user_card_data.user_pubkey != *user_account.key {
+        return Err(BullPosterError::NotUserCardOwner.into());
+    }
+
+    let amount = user_card_data.claimable_rewards;
+    if amount == 0 {
+        return Err(BullPosterError::InsufficientTokens.into());
+    }
+
+    // Verify the token mint
+    token_mint_account.token_mint(program_id, config.token_mint_bump)?;
+
+    // Verify the claim vault against its stored bump
+    let claim_vault_bump = config.claim_vault_bump;
+    let claim_vault_pda =
+        pda::create_address(program_id, &[pda::CLAIM_VAULT_SEED], claim_vault_bump)?;
+    claim_vault_account.address(&claim_vault_pda, BullPosterError::InvalidClaimVault)?;
+
+    msg!("Transferring {} claimed tokens to user", amount);
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            token_program.key,
+            claim_vault_account.key,
+            token_mint_account.key,
+            user_token_account.key,
+            &claim_vault_pda,
+            &[],
+            amount,
+            9,
+        )?,
+        &[
+            claim_vault_account.clone(),
+            token_mint_account.clone(),
+            user_token_account.clone(),
+            claim_vault_account.clone(),
+            token_program.clone(),
+        ],
+        &[&[pda::CLAIM_VAULT_SEED, &[claim_vault_bump]]],
+    )?;
+
+    user_card_data.claimable_rewards = 0;
+    user_card_data.total_rewards += amount;
+
+    let mut data = user_card_account.data.borrow_mut();
+    let bytes_written = user_card_data.custom_serialize(&mut data)?;
+    if bytes_written < user_card_bytes_read {
+        data[bytes_written..user_card_bytes_read].fill(0);
+    }
+
+    msg!("Rewards claimed successfully");
+    Ok(())
+}
+
+fn post_merkle_root(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    merkle_root: [u8; 32],
+    total_amount: u64,
+    leaf_count: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let competition_account = next_account_info(account_info_iter)?
+        .card::<CompetitionCard>(program_id)?
+        .writable()?;
+    let distributor_account = next_account_info(account_info_iter)?.writable()?;
+    let reward_vault_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+    let config_account = next_account_info(account_info_iter)?;
+
+    let config = GlobalConfig::load(program_id, config_account)?;
+
+    // Verify the authority
+    if authority_account.key != &config.authority {
+        return Err(BullPosterError::Unauthorized.into());
+    }
+
+    if leaf_count == 0 || total_amount == 0 {
+        return Err(BullPosterError::InvalidMerkleDistribution.into());
+    }
+    if leaf_count > MerkleDistributorCard::MAX_LEAF_COUNT {
+        msg!(
+            "{} leaves exceed the distributor's limit of {}",
+            leaf_count,
+            MerkleDistributorCard::MAX_LEAF_COUNT
+        );
+        return Err(BullPosterError::InvalidMerkleDistribution.into());
+    }
+
+    let (mut competition_data, comp_bytes_read) =
+        CompetitionCard::custom_deserialize(&competition_account.data.borrow())?;
+
+    // The root replaces the regular distribution, so the competition must be finalized and unpaid
+    if competition_data.status != "finalized" || !competition_data.distributed_rewards.is_empty() {
+        return Err(BullPosterError::InvalidCompetitionStatus.into());
+    }
+
+    // Claims are paid from the reward vault, so it must already hold the whole distribution
+    let reward_vault_pda = pda::create_address(
+        program_id,
+        &[&pda::reward_vault_seed(competition_account.key)],
+        competition_data.reward_vault_bump,
+    )?;
+    reward_vault_account.address(&reward_vault_pda, BullPosterError::InvalidRewardVault)?;
+    let vault_balance = if reward_vault_account.data_is_empty() {
+        0
+    } else {
+        StateWithExtensions::<Account>::unpack(&reward_vault_account.data.borrow())?
+            .base
+            .amount
+    };
+    if vault_balance < total_amount {
+        msg!(
+            "Reward vault holds {} tokens, the distribution needs {}",
+            vault_balance,
+            total_amount
+        );
+        return Err(BullPosterError::RewardVaultUnderfunded.into());
+    }
+
+    // Derive PDA for the competition's merkle distributor
+    let (distributor_pda, bump) =
+        pda::merkle_distributor_address(program_id, competition_account.key);
+    let distributor_seed = pda::merkle_distributor_seed(competition_account.key);
+    if distributor_account.key != &distributor_pda {
+        return Err(BullPosterError::InvalidDistributorAccount.into());
+    }
+
+    if !distributor_account.data_is_empty() {
+        return Err(BullPosterError::MerkleRootAlreadyPosted.into());
+    }
+
+    let distributor_data = MerkleDistributorCard {
+        competition_id: *competition_account.key,
+        merkle_root,
+        total_amount,
+        total_claimed: 0,
+        leaf_count,
+        claimed_bitmap: vec![0u8; ((leaf_count + 7) / 8) as usize],
+        bump,
+    };
+
+    // Size the account exactly so the bitmap can cover every leaf
+    let space = distributor_data.stored_len()?;
+
+    create_pda_account(
+        authority_account,
+        &Rent::get()?,
+        space,
+        program_id,
+        system_program,
+        distributor_account,
+        &[&distributor_seed, &[bump]],
+    )?;
+
+    distributor_data.write_card(&mut distributor_account.data.borrow_mut())?;
+
+    // Mark the competition so the regular distribution and further funding are refused
+    competition_data.distributed_rewards = vec![(*distributor_account.key, total_amount)];
+
+    ensure_account_space(
+        competition_account,
+        authority_account,
+        system_program,
+        competition_data.stored_len()?,
+    )?;
+    let mut data = competition_account.data.borrow_mut();
+    let bytes_written = competition_data.custom_serialize(&mut data)?;
+    if bytes_written < comp_bytes_read {
+        data[bytes_written..comp_bytes_read].fill(0);
+    }
+
+    msg!(
+        "Merkle root posted for {} leaves totalling {} tokens",
+        leaf_count,
+        total_amount
+    );
+    Ok(())
+}
+
+fn claim_with_proof(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    index: u64,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let user_account = next_account_info(account_info_iter)?.signer()?;
+    let user_card_account = next_account_info(account_info_iter)?
+        .card::<UserCard>(program_id)?
+        .writable()?;
+    let competition_account = next_account_info(account_info_iter)?
+        .card::<CompetitionCard>(program_id)?
+        .writable()?;
+    let distributor_account = next_account_info(account_info_iter)?
+        .card::<MerkleDistributorCard>(program_id)?
+        .writable()?;
+    let reward_vault_account = next_account_info(account_info_iter)?.writable()?;
+    let user_token_account = next_account_info(account_info_iter)?.writable()?;
+    let token_mint_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?.token_program()?;
+    let config_account = next_account_info(account_info_iter)?;
+
+    let config = GlobalConfig::load(program_id, config_account)?;
+
+    let mut distributor_data =
+        MerkleDistributorCard::read_card(&distributor_account.data.borrow())?;
+    if distributor_data.competition_id != *competition_account.key {
+        return Err(BullPosterError::DistributorMismatch.into());
+    }
+
+    // Verify the leaf against the posted root
+    let leaf = merkle::leaf_hash(index, user_account.key, amount);
+    if index >= distributor_data.leaf_count
+        || !merkle::verify_proof(&proof, &distributor_data.merkle_root, leaf)
+    {
+        msg!("Invalid merkle proof for leaf {}", index);
+        return Err(BullPosterError::InvalidMerkleProof.into());
+    }
+
+    // Each leaf can only be claimed once
+    let byte_index = (index / 8) as usize;
+    let bit_mask = 1u8 << (index % 8);
+    if distributor_data.claimed_bitmap[byte_index] & bit_mask != 0 {
+        return Err(BullPosterError::LeafAlreadyClaimed.into());
+    }
+
+    let total_claimed = distributor_data
+        .total_claimed
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if total_claimed > distributor_data.total_amount {
+        return Err(BullPosterError::InsufficientTokens.into());
+    }
+
+    let (mut user_card_data, user_card_bytes_read) =
+        UserCard::custom_deserialize(&user_card_account.data.borrow())?;
+    if user_card_data.user_pubkey != *user_account.key {
+        return Err(BullPosterError::NotUserCardOwner.into());
+    }
+
+    let (mut competition_data, comp_bytes_read) =
+        CompetitionCard::custom_deserialize(&competition_account.data.borrow())?;
+
+    // Verify the token mint
+    token_mint_account.token_mint(program_id, config.token_mint_bump)?;
+
+    // Verify the competition's reward vault against its stored bump
+    let reward_vault_bump = competition_data.reward_vault_bump;
+    let reward_vault_seed = pda::reward_vault_seed(competition_account.key);
+    let reward_vault_pda =
+        pda::create_address(program_id, &[&reward_vault_seed], reward_vault_bump)?;
+    if reward_vault_account.key != &reward_vault_pda {
+        return Err(BullPosterError::InvalidRewardVault.into());
+    }
+
+    msg!("Transferring {} tokens for leaf {}", amount, index);
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            token_program.key,
+            reward_vault_account.key,
+            token_mint_account.key,
+            user_token_account.key,
+            &reward_vault_pda,
+            &[],
+            amount,
+            9,
+        )?,
+        &[
+            reward_vault_account.clone(),
+            token_mint_account.clone(),
+            user_token_account.clone(),
+            reward_vault_account.clone(),
+            token_program.clone(),
+        ],
+        &[&[&reward_vault_seed, &[reward_vault_bump]]],
+    )?;
+
+    distributor_data.claimed_bitmap[byte_index] |= bit_mask;
+    distributor_data.total_claimed = total_claimed;
+    distributor_data.write_card(&mut distributor_account.data.borrow_mut())?;
+
+    user_card_data.total_rewards = user_card_data
+        .total_rewards
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let mut data = user_card_account.data.borrow_mut();
+    let bytes_written = user_card_data.custom_serialize(&mut data)?;
+    if bytes_written < user_card_bytes_read {
+        data[bytes_written..user_card_bytes_read].fill(0);
+    }
+    drop(data);
+
+    competition_data.total_rewards_distributed = competition_data
+        .total_rewards_distributed
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let mut data = competition_account.data.borrow_mut();
+    let bytes_written = competition_data.custom_serialize(&mut data)?;
+    if bytes_written < comp_bytes_read {
+        data[bytes_written..comp_bytes_read].fill(0);
+    }
+
+    msg!("Merkle reward claimed successfully");
+    Ok(())
+}
+
+fn create_user_card(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let user_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let user_card_account = next_account_info(account_info_iter)?.writable()?;
+    let program_state_account = next_account_info(account_info_iter)?
+        .card::<ProgramStateCard>(program_id)?
+        .writable()?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
+
+    // Derive PDA for user card account
+    let (user_card_account_pda, bump) = pda::user_card_address(program_id, user_account.key);
+    let seed = pda::user_card_seed(user_account.key);
+
+    // Verify provided user card account matches derived PDA
+    if user_card_account.key != &user_card_account_pda {
+        msg!("Error: User card account does not match derived PDA");
+        return Err(BullPosterError::InvalidUserCardAccount.into());
+    }
+
+    // Check if the account already exists
+    if !user_card_account.data_is_empty() {
+        return Err(BullPosterError::UserCardAlreadyExists.into());
+    }
+
+    // Deserialize ProgramStateCard from account data using custom_deserialize
+    let (mut program_state, bytes_read) = ProgramStateCard::custom_deserialize(
+        &program_state_account.data.borrow(),
+    )
+    .map_err(|e| {
+        msg!("Failed to deserialize ProgramStateCard: {:?}", e);
+        ProgramError::InvalidAccountData
+    })?;
+
+    // Verify program state account with its stored bump
+    let program_state_pda =
+        pda::create_address(program_id, &[pda::PROGRAM_STATE_SEED], program_state.bump)?;
+    if program_state_account.key != &program_state_pda {
+        return Err(BullPosterError::InvalidProgramStateAccount.into());
+    }
+
+    // Create UserCard data
+    let user_card_data = UserCard {
+        user_pubkey: *user_account.key,
+        owned_programs: Vec::new(),
+        enrolled_programs: Vec::new(),
+        is_conducting_raid: false,
         user_email: String::new(),
         user_dob: String::new(),
         user_twitter_handle: String::new(),
@@ -2415,6 +5729,8 @@ fn create_user_card(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
         engagement_score: 0,
         streaks: 0,
         profile_picture_url: String::new(),
+        claimable_rewards: 0,
+        bump,
     };
 
     let space = 1000;
@@ -2431,16 +5747,7 @@ fn create_user_card(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
     )?;
 
     // Serialize and save UserCard data
-    user_card_data.serialize(&mut &mut user_card_account.data.borrow_mut()[..])?;
-
-    // Deserialize ProgramStateCard from account data using custom_deserialize
-    let (mut program_state, bytes_read) = ProgramStateCard::custom_deserialize(
-        &program_state_account.data.borrow(),
-    )
-    .map_err(|e| {
-        msg!("Failed to deserialize ProgramStateCard: {:?}", e);
-        ProgramError::InvalidAccountData
-    })?;
+    user_card_data.custom_serialize(&mut user_card_account.data.borrow_mut())?;
 
     msg!("Bytes read during deserialization: {}", bytes_read);
     let total_space = program_state_account.data.borrow().len();
@@ -2503,43 +5810,50 @@ fn create_user_card(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
 
 fn enroll_in_program(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let user_account = next_account_info(account_info_iter)?;
-    let user_card_account = next_account_info(account_info_iter)?;
-    let raid_program_account = next_account_info(account_info_iter)?;
-
-    // Verify user is signer
-    if !user_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    let user_account = next_account_info(account_info_iter)?.signer()?.writable()?;
+    let user_card_account = next_account_info(account_info_iter)?
+        .card::<UserCard>(program_id)?
+        .writable()?;
+    let raid_program_account =
+        next_account_info(account_info_iter)?.card::<RaidProgramCard>(program_id)?;
+    let system_program = next_account_info(account_info_iter)?.system_program()?;
 
     // Deserialize UserCard
-    let mut user_card_data: UserCard = UserCard::try_from_slice(&user_card_account.data.borrow())?;
+    let (mut user_card_data, user_card_bytes_read) =
+        UserCard::custom_deserialize(&user_card_account.data.borrow())?;
 
     // Verify that the user_card belongs to the signer
     if user_card_data.user_pubkey != *user_account.key {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(BullPosterError::NotUserCardOwner.into());
     }
 
     // Get the raid program public key
-    let raid_program_data: RaidProgramCard =
-        RaidProgramCard::try_from_slice(&raid_program_account.data.borrow())?;
-    let raid_program_pubkey = raid_program_data.raid_program_id.to_string();
+    let (raid_program_data, _) =
+        RaidProgramCard::custom_deserialize(&raid_program_account.data.borrow())?;
+    let raid_program_pubkey = raid_program_data.raid_program_id;
 
     // Check if the user is already enrolled
     if user_card_data
         .enrolled_programs
         .contains(&raid_program_pubkey)
     {
-        return Err(ProgramError::AccountAlreadyInitialized);
+        return Err(BullPosterError::UserAlreadyEnrolled.into());
     }
 
-    user_card_data
-        .enrolled_programs
-        .push_str(&raid_program_pubkey);
-    user_card_data.enrolled_programs.push(',');
+    user_card_data.enrolled_programs.push(raid_program_pubkey);
 
     // Serialize and save the updated UserCard
-    user_card_data.serialize(&mut &mut user_card_account.data.borrow_mut()[..])?;
+    ensure_account_space(
+        user_card_account,
+        user_account,
+        system_program,
+        user_card_data.stored_len()?,
+    )?;
+    let mut data = user_card_account.data.borrow_mut();
+    let bytes_written = user_card_data.custom_serialize(&mut data)?;
+    if bytes_written < user_card_bytes_read {
+        data[bytes_written..user_card_bytes_read].fill(0);
+    }
 
     msg!("User enrolled in program successfully");
     Ok(())
@@ -2554,66 +5868,192 @@ pub fn update_user_card(
     profile_picture_url: String,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let user_account = next_account_info(account_info_iter)?;
-    let user_card_account = next_account_info(account_info_iter)?;
+    let user_account = next_account_info(account_info_iter)?.signer()?;
+    let user_card_account = next_account_info(account_info_iter)?
+        .card::<UserCard>(program_id)?
+        .writable()?;
 
-    // Verify user is signer
-    if !user_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    let (mut user_card_data, _) = UserCard::custom_deserialize(&user_card_account.data.borrow())?;
 
-    // Verify the user card account
-    let (user_card_pda, _) =
-        Pubkey::find_program_address(&[b"user_card", user_account.key.as_ref()], program_id);
+    // Verify the user card account with its stored bump
+    let user_card_pda = pda::create_address(
+        program_id,
+        &[&pda::user_card_seed(user_account.key)],
+        user_card_data.bump,
+    )?;
     if user_card_account.key != &user_card_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(BullPosterError::InvalidUserCardAccount.into());
     }
 
     // Update user card data
-    let mut user_card_data = UserCard::try_from_slice(&user_card_account.data.borrow())?;
     user_card_data.user_email = user_email;
     user_card_data.user_twitter_handle = user_twitter_handle;
     user_card_data.user_dob = user_dob;
     user_card_data.profile_picture_url = profile_picture_url;
 
-    user_card_data.serialize(&mut &mut user_card_account.data.borrow_mut()[..])?;
+    user_card_data.custom_serialize(&mut user_card_account.data.borrow_mut())?;
 
     Ok(())
 }
 
-fn authority_mint(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+// Replace every config parameter except the authority, which moves through ProposeAuthority
+fn update_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: GlobalConfig,
+) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let authority_account = next_account_info(account_info_iter)?;
-    let token_mint = next_account_info(account_info_iter)?;
-    let recipient_token_account = next_account_info(account_info_iter)?;
-    let token_program = next_account_info(account_info_iter)?;
+    let authority_account = next_account_info(account_info_iter)?.signer()?;
+    let config_account = next_account_info(account_info_iter)?.writable()?;
+
+    let config = GlobalConfig::load(program_id, config_account)?;
 
     // Verify the authority
-    if authority_account.key != &AUTHORITY_PUBKEY {
-        return Err(ProgramError::InvalidAccountData);
+    if authority_account.key != &config.authority {
+        return Err(BullPosterError::Unauthorized.into());
     }
 
-    // Ensure the authority is a signer
-    if !authority_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    // Tiers must stay ordered and the timings must leave room to raid
+    if params.required_stake_amount == 0
+        || params.required_stake_amount > params.silver_stake_amount
+        || params.silver_stake_amount > params.gold_stake_amount
+        || params.slash_basis_points > 10_000
+        || params.raid_duration == 0
+        || params.pvp_acceptance_window == 0
+        || params.epoch_length == 0
+        || params.max_supply < config.total_minted
+    {
+        msg!("Invalid config parameters");
+        return Err(BullPosterError::InvalidConfigParameters.into());
     }
 
-    // Derive PDA for token mint
-    let (token_mint_pda, mint_bump) =
-        Pubkey::find_program_address(&[b"pda_token_mint"], program_id);
+    let updated_config = GlobalConfig {
+        authority: config.authority,
+        pending_authority: config.pending_authority,
+        paused: config.paused,
+        paused_instructions: config.paused_instructions,
+        total_minted: config.total_minted,
+        epoch_start: config.epoch_start,
+        epoch_minted: config.epoch_minted,
+        bump: config.bump,
+        token_mint_bump: config.token_mint_bump,
+        claim_vault_bump: config.claim_vault_bump,
+        ..params
+    };
+    updated_config.write_card(&mut config_account.data.borrow_mut())?;
 
-    // Verify the token mint
-    if token_mint.key != &token_mint_pda {
-        return Err(ProgramError::InvalidAccountData);
+    msg!("Config updated: {:?}", updated_config);
+    Ok(())
+}
+
+// First step of an authority transfer; proposing Pubkey::default() cancels a pending transfer
+fn propose_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_authority: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority_account = next_account_info(account_info_iter)?.signer()?;
+    let config_account = next_account_info(account_info_iter)?.writable()?;
+
+    let mut config = GlobalConfig::load(program_id, config_account)?;
+
+    // Verify the authority
+    if authority_account.key != &config.authority {
+        return Err(BullPosterError::Unauthorized.into());
+    }
+
+    config.pending_authority = new_authority;
+    config.write_card(&mut config_account.data.borrow_mut())?;
+
+    msg!("Authority transfer to {} proposed", new_authority);
+    Ok(())
+}
+
+// Second step of an authority transfer. The new authority has to sign, so a
+// multisig or governance PDA proves it can act before it takes over
+fn accept_authority(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let new_authority_account = next_account_info(account_info_iter)?.signer()?;
+    let config_account = next_account_info(account_info_iter)?.writable()?;
+
+    let mut config = GlobalConfig::load(program_id, config_account)?;
+
+    // Verify the pending authority
+    if config.pending_authority == Pubkey::default()
+        || new_authority_account.key != &config.pending_authority
+    {
+        return Err(BullPosterError::NotPendingAuthority.into());
+    }
+
+    config.authority = config.pending_authority;
+    config.pending_authority = Pubkey::default();
+    config.write_card(&mut config_account.data.borrow_mut())?;
+
+    msg!("Authority transferred to {}", config.authority);
+    Ok(())
+}
+
+// Pause everything, or only the instructions whose bits are set
+fn set_pause(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    paused: bool,
+    paused_instructions: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority_account = next_account_info(account_info_iter)?.signer()?;
+    let config_account = next_account_info(account_info_iter)?.writable()?;
+
+    let mut config = GlobalConfig::load(program_id, config_account)?;
+
+    // Verify the authority
+    if authority_account.key != &config.authority {
+        return Err(BullPosterError::Unauthorized.into());
+    }
+
+    config.paused = paused;
+    config.paused_instructions = paused_instructions;
+    config.write_card(&mut config_account.data.borrow_mut())?;
+
+    msg!(
+        "Paused: {}, paused instructions: {:#b}",
+        paused,
+        paused_instructions
+    );
+    Ok(())
+}
+
+fn authority_mint(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let authority_account = next_account_info(account_info_iter)?.signer()?;
+    let token_mint = next_account_info(account_info_iter)?.writable()?;
+    let recipient_token_account = next_account_info(account_info_iter)?.writable()?;
+    let token_program = next_account_info(account_info_iter)?.token_program()?;
+    let config_account = next_account_info(account_info_iter)?.writable()?;
+
+    let mut config = GlobalConfig::load(program_id, config_account)?;
+
+    // Verify the authority
+    if authority_account.key != &config.authority {
+        return Err(BullPosterError::Unauthorized.into());
     }
 
+    // Verify the token mint
+    let mint_bump = config.token_mint_bump;
+    token_mint.token_mint(program_id, mint_bump)?;
+
+    // Enforce the supply cap and emission budget before minting
+    config.record_mint(amount, Clock::get()?.unix_timestamp as u64)?;
+    config.write_card(&mut config_account.data.borrow_mut())?;
+
     // Mint tokens to recipient's ATA
     invoke_signed(
         &token_instruction::mint_to(
             token_program.key,
             token_mint.key,
             recipient_token_account.key,
-            &token_mint_pda,
+            token_mint.key,
             &[],
             amount,
         )?,
@@ -2622,9 +6062,987 @@ fn authority_mint(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) ->
             recipient_token_account.clone(),
             token_program.clone(),
         ],
-        &[&[b"pda_token_mint", &[mint_bump]]],
+        &[&[pda::TOKEN_MINT_SEED, &[mint_bump]]],
     )?;
 
     msg!("Minted {} tokens to recipient", amount);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{packed, TestAccount};
+    use solana_program::system_program;
+
+    fn competition(competition_type: &str, status: &str) -> CompetitionCard {
+        CompetitionCard {
+            competition_id: Pubkey::new_unique(),
+            competition_type: competition_type.to_string(),
+            start_time: 1_000,
+            end_time: 2_000,
+            total_rewards_distributed: 0,
+            status: status.to_string(),
+            enrolled_programs: vec![Pubkey::new_unique()],
+            required_programs: 2,
+            challenger_program_id: None,
+            challenged_program_id: None,
+            start_expiration: None,
+            distributed_rewards: Vec::new(),
+            placements: Vec::new(),
+            reward_cap: 0,
+            reward_vault_bump: 255,
+        }
+    }
+
+    #[test]
+    fn unaccepted_pvp_challenge_expires() {
+        let mut pvp = competition("PvP", "awaiting");
+        pvp.start_expiration = Some(500);
+
+        assert!(!pvp.advance_status(500));
+        assert_eq!(pvp.status, "awaiting");
+
+        assert!(pvp.advance_status(501));
+        assert_eq!(pvp.status, "expired");
+        assert!(!pvp.advance_status(10_000));
+    }
+
+    #[test]
+    fn awaiting_competition_without_expiration_keeps_waiting() {
+        let mut awaiting = competition("4-program", "awaiting");
+
+        assert!(!awaiting.advance_status(u64::MAX));
+        assert_eq!(awaiting.status, "awaiting");
+    }
+
+    #[test]
+    fn active_competition_ends_at_end_time() {
+        let mut active = competition("4-program", "active");
+
+        assert!(!active.advance_status(1_999));
+        assert_eq!(active.status, "active");
+
+        assert!(active.advance_status(2_000));
+        assert_eq!(active.status, "ended");
+        assert!(!active.advance_status(10_000));
+    }
+
+    #[test]
+    fn finalized_competition_is_left_alone() {
+        let mut finalized = competition("4-program", "finalized");
+
+        assert!(!finalized.advance_status(u64::MAX));
+        assert_eq!(finalized.status, "finalized");
+    }
+
+    #[test]
+    fn raids_without_burns_take_no_share() {
+        let mut winner = raid_card(Pubkey::new_unique(), Pubkey::new_unique(), 255);
+        winner.total_burned = 50;
+        let idle = raid_card(Pubkey::new_unique(), Pubkey::new_unique(), 255);
+        let mut slashed = raid_card(Pubkey::new_unique(), Pubkey::new_unique(), 255);
+        slashed.total_burned = 40;
+        slashed.is_slashed = true;
+        let mut last = raid_card(Pubkey::new_unique(), Pubkey::new_unique(), 255);
+        last.total_burned = 10;
+
+        let earns_rewards: Vec<bool> = [&winner, &idle, &slashed, &last]
+            .iter()
+            .map(|raid_card| raid_card.earns_rewards())
+            .collect();
+        assert_eq!(earns_rewards, vec![true, false, false, true]);
+
+        // The two raids that can credit users split the whole pool 2:1, with the
+        // rounding dust going to the winner
+        let shares = placement_shares(1_000, &earns_rewards).unwrap();
+        assert_eq!(shares, vec![667, 0, 0, 333]);
+        assert_eq!(shares.iter().sum::<u64>(), 1_000);
+    }
+
+    #[test]
+    fn no_shares_without_a_raid_that_burned() {
+        assert_eq!(placement_shares(1_000, &[false, false]), None);
+    }
+
+    // Accounts for leave_competition: user, raid program, competition and raid card,
+    // with the program enrolled in the competition and conducting its raid
+    fn leave_competition_accounts(
+        program_id: &Pubkey,
+        mut competition_card: CompetitionCard,
+    ) -> [TestAccount; 4] {
+        let user = Pubkey::new_unique();
+        let (raid_program_key, bump) = pda::raid_program_address(program_id, &user, "Bulls");
+        let competition_key = competition_card.competition_id;
+        let (raid_card_key, raid_card_bump) =
+            pda::raid_card_address(program_id, &competition_key, &raid_program_key);
+
+        let mut raid_program_card = raid_program(raid_program_key, user, bump, 255);
+        raid_program_card.active_raid_id = raid_card_key;
+        raid_program_card.raids.push(raid_card_key);
+        competition_card.enrolled_programs.push(raid_program_key);
+
+        [
+            TestAccount::new(user, system_program::id(), Vec::new()),
+            TestAccount::new(raid_program_key, *program_id, packed(&raid_program_card)),
+            TestAccount::new(competition_key, *program_id, packed(&competition_card)),
+            TestAccount::new(
+                raid_card_key,
+                *program_id,
+                packed(&raid_card(
+                    competition_key,
+                    raid_program_key,
+                    raid_card_bump,
+                )),
+            ),
+        ]
+    }
+
+    fn run_leave_competition(
+        program_id: &Pubkey,
+        accounts: &mut [TestAccount; 4],
+    ) -> ProgramResult {
+        let [user, raid_program_account, competition_account, raid_card_account] = accounts;
+        leave_competition(
+            program_id,
+            &[
+                user.info(),
+                raid_program_account.info(),
+                competition_account.info(),
+                raid_card_account.info(),
+            ],
+        )
+    }
+
+    #[test]
+    fn expired_challenger_leaves_and_can_withdraw_its_stake() {
+        let program_id = Pubkey::new_unique();
+        let mut pvp = competition("PvP", "awaiting");
+        pvp.enrolled_programs.clear();
+        pvp.start_expiration = Some(500);
+        assert!(pvp.advance_status(501));
+
+        let mut accounts = leave_competition_accounts(&program_id, pvp);
+        run_leave_competition(&program_id, &mut accounts).unwrap();
+
+        let [user, raid_program_account, competition_account, raid_card_account] = &accounts;
+        let (raid_program_data, _) =
+            RaidProgramCard::custom_deserialize(&raid_program_account.data).unwrap();
+        // withdraw_stake only refuses while the program is conducting a raid
+        assert!(!raid_program_data.is_conducting_raid);
+        assert_eq!(raid_program_data.active_raid_id, Pubkey::default());
+        assert!(!raid_program_data.raids.contains(&raid_card_account.key));
+
+        let (competition_data, _) =
+            CompetitionCard::custom_deserialize(&competition_account.data).unwrap();
+        assert_eq!(competition_data.status, "expired");
+        assert!(competition_data.enrolled_programs.is_empty());
+
+        assert_eq!(user.lamports, 2);
+        assert_eq!(raid_card_account.lamports, 0);
+        assert!(raid_card_account.data.iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn program_leaves_a_competition_that_never_filled() {
+        let program_id = Pubkey::new_unique();
+        let awaiting = competition("4-program", "awaiting");
+        let other_program = awaiting.enrolled_programs[0];
+
+        let mut accounts = leave_competition_accounts(&program_id, awaiting);
+        run_leave_competition(&program_id, &mut accounts).unwrap();
+
+        let (competition_data, _) = CompetitionCard::custom_deserialize(&accounts[2].data).unwrap();
+        assert_eq!(competition_data.status, "awaiting");
+        assert_eq!(competition_data.enrolled_programs, vec![other_program]);
+    }
+
+    #[test]
+    fn programs_cannot_leave_a_started_competition() {
+        let program_id = Pubkey::new_unique();
+        let mut accounts =
+            leave_competition_accounts(&program_id, competition("4-program", "active"));
+
+        assert_eq!(
+            run_leave_competition(&program_id, &mut accounts),
+            Err(BullPosterError::InvalidCompetitionStatus.into())
+        );
+    }
+
+    // Run UpdateUserCard for `user` against a user card stored at `card_address`
+    fn run_update_user_card(
+        program_id: &Pubkey,
+        user: Pubkey,
+        card_address: Pubkey,
+        bump: u8,
+    ) -> ProgramResult {
+        let mut user_account = TestAccount::new(user, system_program::id(), Vec::new());
+        let mut card_account =
+            TestAccount::new(card_address, *program_id, packed(&user_card(user, bump)));
+        update_user_card(
+            program_id,
+            &[user_account.info(), card_account.info()],
+            "new@example.com".to_string(),
+            "new".to_string(),
+            "2000-01-01".to_string(),
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn update_user_card_accepts_the_module_address() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let (address, bump) = pda::user_card_address(&program_id, &user);
+
+        assert_eq!(
+            run_update_user_card(&program_id, user, address, bump),
+            Ok(())
+        );
+    }
+
+    // UpdateUserCard used to derive the card from [b"user_card", user], which never
+    // matched the hashed seed that CreateUserCard created it at
+    #[test]
+    fn update_user_card_rejects_the_old_unhashed_address() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let (address, bump) = pda::user_card_address(&program_id, &user);
+        let old_address =
+            Pubkey::find_program_address(&[b"user_card", user.as_ref()], &program_id).0;
+        assert_ne!(old_address, address);
+
+        assert_eq!(
+            run_update_user_card(&program_id, user, old_address, bump),
+            Err(BullPosterError::InvalidUserCardAccount.into())
+        );
+    }
+
+    #[test]
+    fn config_load_checks_the_config_address() {
+        let program_id = Pubkey::new_unique();
+        let (address, bump) = pda::config_address(&program_id);
+        let config = GlobalConfig {
+            bump,
+            ..GlobalConfig::default()
+        };
+
+        for (key, expected) in [
+            (address, Ok(())),
+            (
+                Pubkey::new_unique(),
+                Err(BullPosterError::InvalidConfigAccount.into()),
+            ),
+        ] {
+            let mut config_account = TestAccount::new(key, program_id, packed(&config));
+            assert_eq!(
+                GlobalConfig::load(&program_id, &config_account.info()).map(|_| ()),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn create_raid_rejects_a_spoofed_current_competition() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let (raid_program_key, bump) = pda::raid_program_address(&program_id, &user, "Bulls");
+        let mut raid_program_card = raid_program(raid_program_key, user, bump, 255);
+        raid_program_card.is_conducting_raid = false;
+        let (config_key, config_bump) = pda::config_address(&program_id);
+        let config = GlobalConfig {
+            bump: config_bump,
+            ..GlobalConfig::default()
+        };
+        let (program_state_key, program_state_bump) = pda::program_state_address(&program_id);
+        let program_state = ProgramStateCard {
+            last_seen_raids: last_seen_raids(&program_id),
+            registered_programs_count: 1,
+            registered_users_count: 1,
+            registered_raids_count: 0,
+            registered_competitions_count: 3,
+            bump: program_state_bump,
+        };
+
+        // An older competition of the same type, still awaiting programs
+        let older_key = pda::competition_address(&program_id, "4-program", 2, None).0;
+        let mut older = competition("4-program", "awaiting");
+        older.competition_id = older_key;
+        let mut raid_card_account = TestAccount::new(
+            pda::raid_card_address(&program_id, &older_key, &raid_program_key).0,
+            system_program::id(),
+            Vec::new(),
+        );
+
+        for (mut current_competition, expected) in [
+            (
+                TestAccount::new(older_key, program_id, packed(&older)),
+                BullPosterError::InvalidCompetitionAccount,
+            ),
+            // The current address, but holding something other than a competition
+            (
+                TestAccount::new(
+                    program_state.last_seen_raids[1].competition_id,
+                    program_id,
+                    packed(&raid_card(older_key, raid_program_key, 255)),
+                ),
+                BullPosterError::InvalidAccountDiscriminator,
+            ),
+        ] {
+            let mut user_account = TestAccount::new(user, system_program::id(), Vec::new());
+            let mut new_competition = TestAccount::new(
+                pda::competition_address(&program_id, "4-program", 4, None).0,
+                system_program::id(),
+                Vec::new(),
+            );
+            let mut raid_program_account =
+                TestAccount::new(raid_program_key, program_id, packed(&raid_program_card));
+            let mut system = TestAccount::new(system_program::id(), Pubkey::default(), Vec::new());
+            let mut new_raid_card =
+                TestAccount::new(Pubkey::new_unique(), system_program::id(), Vec::new());
+            let mut program_state_account =
+                TestAccount::new(program_state_key, program_id, packed(&program_state));
+            let mut raids_page = TestAccount::new(Pubkey::new_unique(), program_id, Vec::new());
+            let mut competitions_page =
+                TestAccount::new(Pubkey::new_unique(), program_id, Vec::new());
+            let mut config_account = TestAccount::new(config_key, program_id, packed(&config));
+
+            assert_eq!(
+                create_raid(
+                    &program_id,
+                    &[
+                        user_account.info(),
+                        current_competition.info(),
+                        new_competition.info(),
+                        raid_program_account.info(),
+                        system.info(),
+                        raid_card_account.info(),
+                        new_raid_card.info(),
+                        program_state_account.info(),
+                        raids_page.info(),
+                        competitions_page.info(),
+                        config_account.info(),
+                    ],
+                    "4-program".to_string(),
+                ),
+                Err(expected.into())
+            );
+        }
+    }
+
+    #[test]
+    fn largest_merkle_distributor_fits_a_cpi_allocation() {
+        let distributor = |leaf_count: u64| MerkleDistributorCard {
+            competition_id: Pubkey::new_unique(),
+            merkle_root: [7; 32],
+            total_amount: 1_000,
+            total_claimed: 0,
+            leaf_count,
+            claimed_bitmap: vec![0u8; leaf_count.div_ceil(8) as usize],
+            bump: 255,
+        };
+
+        assert_eq!(
+            distributor(MerkleDistributorCard::MAX_LEAF_COUNT)
+                .stored_len()
+                .unwrap(),
+            MAX_PERMITTED_DATA_INCREASE
+        );
+        assert!(
+            distributor(MerkleDistributorCard::MAX_LEAF_COUNT + 8)
+                .stored_len()
+                .unwrap()
+                > MAX_PERMITTED_DATA_INCREASE
+        );
+    }
+
+    // Migration tests. Each card is built in its current layout, written out in an
+    // older one and upgraded; the result must pack to the same bytes.
+
+    fn user_card(user: Pubkey, bump: u8) -> UserCard {
+        UserCard {
+            user_pubkey: user,
+            owned_programs: vec![Pubkey::new_unique()],
+            enrolled_programs: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            is_conducting_raid: true,
+            user_email: "user@example.com".to_string(),
+            user_dob: "2000-01-01".to_string(),
+            user_twitter_handle: "user".to_string(),
+            total_rewards: 10,
+            participated_raids: 2,
+            raid_ranking: 3,
+            engagement_score: 4,
+            streaks: 5,
+            profile_picture_url: "https://example.com/user.png".to_string(),
+            claimable_rewards: 0,
+            bump,
+        }
+    }
+
+    fn raid_card(competition: Pubkey, raid_program: Pubkey, bump: u8) -> RaidCard {
+        RaidCard {
+            competition_id: competition,
+            raid_program_id: raid_program,
+            raid_id: Pubkey::new_unique(),
+            distributed_rewards: vec![(Pubkey::new_unique(), 7), (Pubkey::new_unique(), 3)],
+            placements: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            total_burned: 0,
+            reward_amount: 0,
+            is_slashed: false,
+            bump,
+        }
+    }
+
+    fn raid_program(
+        raid_program_id: Pubkey,
+        owner: Pubkey,
+        bump: u8,
+        token_account_bump: u8,
+    ) -> RaidProgramCard {
+        RaidProgramCard {
+            raid_program_id,
+            name: "Bulls".to_string(),
+            description: "Raiders".to_string(),
+            user_key: owner,
+            profile_picture_url: String::new(),
+            pvp_requests: vec![Pubkey::new_unique()],
+            raids: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+            is_conducting_raid: true,
+            active_raid_id: Pubkey::new_unique(),
+            size: 12,
+            total_rewards_distributed: 100,
+            total_raid_wins: 1,
+            total_raids_partaken: 4,
+            program_rank: 2,
+            pending_unstake_amount: 0,
+            unstake_available_at: 0,
+            is_deactivated: false,
+            staked_amount: 0,
+            bump,
+            token_account_bump,
+        }
+    }
+
+    fn last_seen_raids(program_id: &Pubkey) -> [LastSeenRaid; 5] {
+        let mut last_seen_raids = [LastSeenRaid::default(); 5];
+        last_seen_raids[1] = LastSeenRaid {
+            sequence: 3,
+            competition_id: pda::competition_address(program_id, "4-program", 3, None).0,
+        };
+        last_seen_raids
+    }
+
+    // Legacy accounts were created with 1000 bytes and zero padded after the card
+    fn legacy_data<T: BorshSerialize>(legacy_card: &T) -> Vec<u8> {
+        let mut data = legacy_card.try_to_vec().unwrap();
+        data.resize(1000, 0);
+        data
+    }
+
+    fn to_csv(keys: &[Pubkey]) -> String {
+        keys.iter().map(|key| format!("{},", key)).collect()
+    }
+
+    fn to_rewards_json(rewards: &[(Pubkey, u64)]) -> String {
+        let entries: serde_json::Map<String, serde_json::Value> = rewards
+            .iter()
+            .map(|(key, amount)| (key.to_string(), (*amount).into()))
+            .collect();
+        serde_json::Value::Object(entries).to_string()
+    }
+
+    fn to_placements_json(placements: &[Pubkey]) -> String {
+        serde_json::to_string(&placements.iter().map(Pubkey::to_string).collect::<Vec<_>>())
+            .unwrap()
+    }
+
+    // Write `card` in its version 1 layout, which lacked the trailing `added_len`
+    // bytes of bumps, and check that upgrading it restores the card
+    fn assert_upgrades_from_v1<T: Card>(
+        program_id: &Pubkey,
+        address: &Pubkey,
+        card: T,
+        added_len: usize,
+    ) {
+        let current = packed(&card);
+        let body = &current[CARD_HEADER_LEN..current.len() - added_len];
+
+        let upgraded = T::upgrade(program_id, address, 1, body).unwrap();
+        assert_eq!(packed(&upgraded), current, "{}", std::any::type_name::<T>());
+    }
+
+    fn run_migrate_account(program_id: &Pubkey, target: &mut TestAccount) -> ProgramResult {
+        let mut payer = TestAccount::new(Pubkey::new_unique(), system_program::id(), Vec::new());
+        let mut system = TestAccount::new(system_program::id(), Pubkey::default(), Vec::new());
+        migrate_account(program_id, &[payer.info(), target.info(), system.info()])
+    }
+
+    #[test]
+    fn every_v1_card_upgrades_to_the_current_layout() {
+        let program_id = Pubkey::new_unique();
+        let (user, competition_key, raid_program_key) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        let (address, bump) = pda::config_address(&program_id);
+        let config = GlobalConfig {
+            authority: Pubkey::new_unique(),
+            max_supply: 1_000,
+            bump,
+            token_mint_bump: pda::token_mint_address(&program_id).1,
+            claim_vault_bump: pda::claim_vault_address(&program_id).1,
+            ..GlobalConfig::default()
+        };
+        assert_upgrades_from_v1(&program_id, &address, config, 3);
+
+        let (address, bump) = pda::program_state_address(&program_id);
+        let program_state = ProgramStateCard {
+            last_seen_raids: last_seen_raids(&program_id),
+            registered_programs_count: 4,
+            registered_users_count: 9,
+            registered_raids_count: 31,
+            registered_competitions_count: 2,
+            bump,
+        };
+        assert_upgrades_from_v1(&program_id, &address, program_state, 1);
+
+        let (address, bump) = pda::registry_page_address(&program_id, pda::RAIDS_REGISTRY_SEED, 1);
+        let page = RegistryPageCard {
+            page_index: 1,
+            entries: vec![Pubkey::new_unique()],
+            bump,
+        };
+        assert_upgrades_from_v1(&program_id, &address, page, 1);
+
+        let (address, bump) =
+            pda::raid_card_address(&program_id, &competition_key, &raid_program_key);
+        let raid = raid_card(competition_key, raid_program_key, bump);
+        assert_upgrades_from_v1(&program_id, &address, raid, 1);
+
+        let mut competition_card = competition("4-program", "active");
+        competition_card.competition_id = competition_key;
+        competition_card.reward_vault_bump =
+            pda::reward_vault_address(&program_id, &competition_key).1;
+        assert_upgrades_from_v1(&program_id, &competition_key, competition_card, 1);
+
+        let (address, bump) = pda::raid_program_address(&program_id, &user, "Bulls");
+        let token_account_bump = pda::raid_program_token_account_address(&program_id, &address).1;
+        let mut raid_program_card = raid_program(address, user, bump, token_account_bump);
+        raid_program_card.staked_amount = 5_000;
+        assert_upgrades_from_v1(&program_id, &address, raid_program_card, 2);
+
+        let (address, bump) = pda::user_card_address(&program_id, &user);
+        let mut user_card_v1 = user_card(user, bump);
+        user_card_v1.claimable_rewards = 8;
+        assert_upgrades_from_v1(&program_id, &address, user_card_v1, 1);
+
+        let (address, bump) = pda::merkle_distributor_address(&program_id, &competition_key);
+        let distributor = MerkleDistributorCard {
+            competition_id: competition_key,
+            merkle_root: [7; 32],
+            total_amount: 100,
+            total_claimed: 40,
+            leaf_count: 3,
+            claimed_bitmap: vec![0b101],
+            bump,
+        };
+        assert_upgrades_from_v1(&program_id, &address, distributor, 1);
+
+        let (address, bump) = pda::leaderboard_address(&program_id);
+        let leaderboard = ProgramLeaderboardStateCard {
+            leaderboard_data: "{\"4-program\":[]}".to_string(),
+            bump,
+        };
+        assert_upgrades_from_v1(&program_id, &address, leaderboard, 1);
+    }
+
+    #[test]
+    fn legacy_user_card_upgrades() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let (address, bump) = pda::user_card_address(&program_id, &user);
+        let expected = user_card(user, bump);
+
+        let legacy_card = legacy::UserCard {
+            user_pubkey: user,
+            owned_programs: to_csv(&expected.owned_programs),
+            enrolled_programs: to_csv(&expected.enrolled_programs),
+            is_conducting_raid: expected.is_conducting_raid,
+            user_email: expected.user_email.clone(),
+            user_dob: expected.user_dob.clone(),
+            user_twitter_handle: expected.user_twitter_handle.clone(),
+            total_rewards: expected.total_rewards,
+            participated_raids: expected.participated_raids,
+            raid_ranking: expected.raid_ranking,
+            engagement_score: expected.engagement_score,
+            streaks: expected.streaks,
+            profile_picture_url: expected.profile_picture_url.clone(),
+        };
+        let data = legacy_data(&legacy_card);
+
+        let upgraded = UserCard::upgrade(&program_id, &address, LEGACY_VERSION, &data).unwrap();
+        assert_eq!(packed(&upgraded), packed(&expected));
+
+        assert_eq!(
+            UserCard::upgrade(&program_id, &Pubkey::new_unique(), LEGACY_VERSION, &data)
+                .map(|_| ()),
+            Err(BullPosterError::InvalidUserCardAccount.into())
+        );
+    }
+
+    #[test]
+    fn legacy_raid_card_upgrades() {
+        let program_id = Pubkey::new_unique();
+        let (competition_key, raid_program_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (address, bump) =
+            pda::raid_card_address(&program_id, &competition_key, &raid_program_key);
+        let mut expected = raid_card(competition_key, raid_program_key, bump);
+        // The legacy rewards were a JSON object, which serde_json keeps in key order
+        expected.distributed_rewards.sort();
+
+        let legacy_card = legacy::RaidCard {
+            competition_id: competition_key,
+            raid_program_id: raid_program_key,
+            raid_id: expected.raid_id,
+            distributed_rewards: to_rewards_json(&expected.distributed_rewards),
+            placements: to_placements_json(&expected.placements),
+        };
+        let data = legacy_data(&legacy_card);
+
+        let upgraded = RaidCard::upgrade(&program_id, &address, LEGACY_VERSION, &data).unwrap();
+        assert_eq!(packed(&upgraded), packed(&expected));
+
+        assert_eq!(
+            RaidCard::upgrade(&program_id, &Pubkey::new_unique(), LEGACY_VERSION, &data)
+                .map(|_| ()),
+            Err(BullPosterError::InvalidRaidCardAccount.into())
+        );
+    }
+
+    #[test]
+    fn legacy_competition_card_upgrades() {
+        let program_id = Pubkey::new_unique();
+        let address = pda::competition_address(&program_id, "PvP", 1, None).0;
+        let mut expected = competition("PvP", "awaiting");
+        expected.competition_id = address;
+        expected.enrolled_programs = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        expected.challenger_program_id = Some(expected.enrolled_programs[0]);
+        expected.challenged_program_id = Some(expected.enrolled_programs[1]);
+        expected.start_expiration = Some(1_300);
+        expected.distributed_rewards = vec![(Pubkey::new_unique(), 50)];
+        expected.placements = vec![Pubkey::new_unique()];
+        expected.reward_vault_bump = pda::reward_vault_address(&program_id, &address).1;
+
+        let legacy_card = legacy::CompetitionCard {
+            competition_id: address,
+            competition_type: expected.competition_type.clone(),
+            start_time: expected.start_time,
+            end_time: expected.end_time,
+            total_rewards_distributed: expected.total_rewards_distributed,
+            status: expected.status.clone(),
+            enrolled_programs: to_csv(&expected.enrolled_programs),
+            required_programs: expected.required_programs,
+            challenger_program_id: expected.challenger_program_id,
+            challenged_program_id: expected.challenged_program_id,
+            start_expiration: expected.start_expiration,
+            distributed_rewards: to_rewards_json(&expected.distributed_rewards),
+            placements: to_placements_json(&expected.placements),
+        };
+        let data = legacy_data(&legacy_card);
+
+        let upgraded =
+            CompetitionCard::upgrade(&program_id, &address, LEGACY_VERSION, &data).unwrap();
+        assert_eq!(packed(&upgraded), packed(&expected));
+
+        assert_eq!(
+            CompetitionCard::upgrade(&program_id, &Pubkey::new_unique(), LEGACY_VERSION, &data)
+                .map(|_| ()),
+            Err(BullPosterError::InvalidCompetitionAccount.into())
+        );
+    }
+
+    #[test]
+    fn legacy_raid_program_card_upgrades() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let (address, bump) = pda::raid_program_address(&program_id, &owner, "Bulls");
+        let token_account_bump = pda::raid_program_token_account_address(&program_id, &address).1;
+        let expected = raid_program(address, owner, bump, token_account_bump);
+
+        let legacy_card = legacy::RaidProgramCard {
+            raid_program_id: address,
+            name: expected.name.clone(),
+            description: expected.description.clone(),
+            user_key: owner,
+            profile_picture_url: expected.profile_picture_url.clone(),
+            pvp_requests: to_csv(&expected.pvp_requests),
+            raids: to_csv(&expected.raids),
+            is_conducting_raid: expected.is_conducting_raid,
+            active_raid_id: expected.active_raid_id,
+            size: expected.size,
+            total_rewards_distributed: expected.total_rewards_distributed,
+            total_raid_wins: expected.total_raid_wins,
+            total_raids_partaken: expected.total_raids_partaken,
+            program_rank: expected.program_rank,
+        };
+        let data = legacy_data(&legacy_card);
+
+        let upgraded =
+            RaidProgramCard::upgrade(&program_id, &address, LEGACY_VERSION, &data).unwrap();
+        assert_eq!(packed(&upgraded), packed(&expected));
+
+        assert_eq!(
+            RaidProgramCard::upgrade(&program_id, &Pubkey::new_unique(), LEGACY_VERSION, &data)
+                .map(|_| ()),
+            Err(BullPosterError::InvalidRaidProgramAccount.into())
+        );
+    }
+
+    #[test]
+    fn legacy_singletons_upgrade() {
+        let program_id = Pubkey::new_unique();
+
+        let (address, bump) = pda::program_state_address(&program_id);
+        let seed = pda::competition_seed("4-program", 3, None);
+        let legacy_state = legacy::ProgramStateCard {
+            last_seen_raids: serde_json::json!({
+                "4-program": { "sequence": 3, "competition_id": &seed[..] }
+            })
+            .to_string(),
+            registered_programs_count: 4,
+            registered_users_count: 9,
+        };
+        let expected = ProgramStateCard {
+            last_seen_raids: last_seen_raids(&program_id),
+            registered_programs_count: 0,
+            registered_users_count: 9,
+            registered_raids_count: 0,
+            registered_competitions_count: 0,
+            bump,
+        };
+        let upgraded = ProgramStateCard::upgrade(
+            &program_id,
+            &address,
+            LEGACY_VERSION,
+            &legacy_data(&legacy_state),
+        )
+        .unwrap();
+        assert_eq!(packed(&upgraded), packed(&expected));
+
+        let (address, bump) = pda::leaderboard_address(&program_id);
+        let legacy_leaderboard = legacy::ProgramLeaderboardStateCard {
+            leaderboard_data: "{}".to_string(),
+        };
+        let expected = ProgramLeaderboardStateCard {
+            leaderboard_data: "{}".to_string(),
+            bump,
+        };
+        let upgraded = ProgramLeaderboardStateCard::upgrade(
+            &program_id,
+            &address,
+            LEGACY_VERSION,
+            &legacy_data(&legacy_leaderboard),
+        )
+        .unwrap();
+        assert_eq!(packed(&upgraded), packed(&expected));
+    }
+
+    #[test]
+    fn second_migration_is_a_no_op() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let (address, bump) = pda::user_card_address(&program_id, &user);
+        let expected = packed(&user_card(user, bump));
+
+        // Version 1 card, zero padded like a real account
+        let mut data = expected[..expected.len() - 1].to_vec();
+        data[8] = 1;
+        data.resize(1000, 0);
+        let mut target = TestAccount::new(address, program_id, data);
+
+        assert_eq!(run_migrate_account(&program_id, &mut target), Ok(()));
+        assert_eq!(target.data[..expected.len()], expected[..]);
+        let migrated = target.data.clone();
+
+        assert_eq!(run_migrate_account(&program_id, &mut target), Ok(()));
+        assert_eq!(target.data, migrated);
+    }
+
+    #[test]
+    fn legacy_account_migrates_once() {
+        let program_id = Pubkey::new_unique();
+        let competition_key = pda::competition_address(&program_id, "4-program", 2, None).0;
+        let raid_program_key = Pubkey::new_unique();
+        let (address, bump) =
+            pda::raid_card_address(&program_id, &competition_key, &raid_program_key);
+        let mut expected = raid_card(competition_key, raid_program_key, bump);
+        expected.distributed_rewards.clear();
+        expected.placements.clear();
+
+        // Legacy raid cards were created with empty reward and placement strings
+        let legacy_card = legacy::RaidCard {
+            competition_id: competition_key,
+            raid_program_id: raid_program_key,
+            raid_id: expected.raid_id,
+            distributed_rewards: String::new(),
+            placements: String::new(),
+        };
+        let mut target = TestAccount::new(address, program_id, legacy_data(&legacy_card));
+
+        assert_eq!(run_migrate_account(&program_id, &mut target), Ok(()));
+        let expected = packed(&expected);
+        assert_eq!(target.data[..expected.len()], expected[..]);
+        let migrated = target.data.clone();
+
+        assert_eq!(run_migrate_account(&program_id, &mut target), Ok(()));
+        assert_eq!(target.data, migrated);
+    }
+
+    #[test]
+    fn unrecognized_legacy_account_is_rejected() {
+        let program_id = Pubkey::new_unique();
+        let legacy_card = legacy::RaidCard {
+            competition_id: Pubkey::new_unique(),
+            raid_program_id: Pubkey::new_unique(),
+            raid_id: Pubkey::new_unique(),
+            distributed_rewards: String::new(),
+            placements: String::new(),
+        };
+        let mut target =
+            TestAccount::new(Pubkey::new_unique(), program_id, legacy_data(&legacy_card));
+
+        assert_eq!(
+            run_migrate_account(&program_id, &mut target),
+            Err(BullPosterError::InvalidAccountDiscriminator.into())
+        );
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let (address, bump) = pda::user_card_address(&program_id, &user);
+        let mut data = packed(&user_card(user, bump));
+        data[8] = UserCard::VERSION + 1;
+        let mut target = TestAccount::new(address, program_id, data);
+
+        assert_eq!(
+            run_migrate_account(&program_id, &mut target),
+            Err(BullPosterError::UnsupportedAccountVersion.into())
+        );
+    }
+
+    #[test]
+    fn legacy_program_state_migrates_with_its_registries() {
+        let program_id = Pubkey::new_unique();
+        let (address, bump) = pda::program_state_address(&program_id);
+        let legacy_state = legacy::ProgramStateCard {
+            last_seen_raids: String::new(),
+            registered_programs_count: 0,
+            registered_users_count: 5,
+        };
+        let mut target = TestAccount::new(address, program_id, legacy_data(&legacy_state));
+        let mut payer = TestAccount::new(Pubkey::new_unique(), system_program::id(), Vec::new());
+        let mut system = TestAccount::new(system_program::id(), Pubkey::default(), Vec::new());
+
+        // Registries nothing was registered in, so no page has to be created
+        let mut registry_accounts = Vec::new();
+        for (legacy_seed, seed) in [
+            (
+                legacy::RAID_PROGRAMS_STATE_SEED,
+                pda::RAID_PROGRAMS_REGISTRY_SEED,
+            ),
+            (legacy::RAIDS_STATE_SEED, pda::RAIDS_REGISTRY_SEED),
+            (
+                legacy::COMPETITIONS_STATE_SEED,
+                pda::COMPETITIONS_REGISTRY_SEED,
+            ),
+        ] {
+            let legacy_registry = legacy::RegistryCard {
+                pubkeys: String::new(),
+            };
+            registry_accounts.push(TestAccount::new(
+                Pubkey::find_program_address(&[legacy_seed], &program_id).0,
+                program_id,
+                legacy_data(&legacy_registry),
+            ));
+            registry_accounts.push(TestAccount::new(
+                pda::registry_page_address(&program_id, seed, 0).0,
+                system_program::id(),
+                Vec::new(),
+            ));
+        }
+
+        let mut accounts = vec![payer.info(), target.info(), system.info()];
+        accounts.extend(registry_accounts.iter_mut().map(TestAccount::info));
+        assert_eq!(migrate_account(&program_id, &accounts), Ok(()));
+        drop(accounts);
+
+        let expected = packed(&ProgramStateCard {
+            last_seen_raids: [LastSeenRaid::default(); 5],
+            registered_programs_count: 0,
+            registered_users_count: 5,
+            registered_raids_count: 0,
+            registered_competitions_count: 0,
+            bump,
+        });
+        assert_eq!(target.data[..expected.len()], expected[..]);
+    }
+
+    fn run_initialize_config(
+        program_id: &Pubkey,
+        initializer: Pubkey,
+        config: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut initializer = TestAccount::new(initializer, system_program::id(), Vec::new());
+        let mut token_mint = TestAccount::new(
+            pda::token_mint_address(program_id).0,
+            spl_token_2022::id(),
+            Vec::new(),
+        );
+        let mut system = TestAccount::new(system_program::id(), Pubkey::default(), Vec::new());
+        initialize_config(
+            program_id,
+            &[
+                initializer.info(),
+                config.info(),
+                token_mint.info(),
+                system.info(),
+            ],
+            None,
+        )
+    }
+
+    #[test]
+    fn config_bootstrap_rejects_an_existing_config() {
+        let program_id = Pubkey::new_unique();
+        let config = GlobalConfig::with_defaults(&program_id, AUTHORITY_PUBKEY, 0);
+        let mut config_account = TestAccount::new(
+            pda::config_address(&program_id).0,
+            program_id,
+            packed(&config),
+        );
+
+        assert_eq!(
+            run_initialize_config(&program_id, AUTHORITY_PUBKEY, &mut config_account),
+            Err(BullPosterError::ConfigAlreadyExists.into())
+        );
+    }
+
+    #[test]
+    fn config_bootstrap_requires_the_deploy_authority() {
+        let program_id = Pubkey::new_unique();
+        let mut config_account = TestAccount::new(
+            pda::config_address(&program_id).0,
+            system_program::id(),
+            Vec::new(),
+        );
+
+        assert_eq!(
+            run_initialize_config(&program_id, Pubkey::new_unique(), &mut config_account),
+            Err(BullPosterError::Unauthorized.into())
+        );
+    }
+}
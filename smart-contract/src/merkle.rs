@@ -0,0 +1,317 @@
+// Merkle tree used to distribute competition rewards to large numbers of users.
+//
+// Leaves are `hash(0x00 || index || user || amount)` and inner nodes are
+// `hash(0x01 || min(a, b) || max(a, b))`, so proofs need no left/right flags and
+// a leaf can never be passed off as an inner node. The `index` is the leaf's
+// position in the payout list and selects its bit in the claimed bitmap.
+
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+pub fn leaf_hash(index: u64, user: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[
+        LEAF_PREFIX,
+        &index.to_le_bytes(),
+        user.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+pub fn node_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[NODE_PREFIX, a, b]).to_bytes()
+    } else {
+        hashv(&[NODE_PREFIX, b, a]).to_bytes()
+    }
+}
+
+pub fn verify_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| node_hash(&node, sibling));
+    computed == *root
+}
+
+// Off-chain helpers for building the tree from a payout list
+
+#[cfg(not(target_os = "solana"))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Payout {
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[cfg(not(target_os = "solana"))]
+pub struct MerkleTree {
+    pub payouts: Vec<Payout>,
+    layers: Vec<Vec<[u8; 32]>>, // layers[0] holds the leaves, the last layer holds the root
+}
+
+#[cfg(not(target_os = "solana"))]
+impl MerkleTree {
+    pub fn new(payouts: Vec<Payout>) -> Result<Self, String> {
+        if payouts.is_empty() {
+            return Err("Cannot build a merkle tree without payouts".to_string());
+        }
+
+        let leaves: Vec<[u8; 32]> = payouts
+            .iter()
+            .enumerate()
+            .map(|(index, payout)| leaf_hash(index as u64, &payout.user, payout.amount))
+            .collect();
+
+        // An odd node out is carried up to the next layer unchanged
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => node_hash(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+
+        Ok(Self { payouts, layers })
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap()[0]
+    }
+
+    pub fn leaf_count(&self) -> u64 {
+        self.payouts.len() as u64
+    }
+
+    pub fn total_amount(&self) -> u64 {
+        self.payouts.iter().map(|payout| payout.amount).sum()
+    }
+
+    pub fn proof(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        if index >= self.payouts.len() {
+            return None;
+        }
+
+        let mut proof = Vec::new();
+        let mut position = index;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let sibling = position ^ 1;
+            if sibling < layer.len() {
+                proof.push(layer[sibling]);
+            }
+            position /= 2;
+        }
+        Some(proof)
+    }
+}
+
+// Parse `user,amount` lines; a header line and blank lines are skipped
+#[cfg(not(target_os = "solana"))]
+pub fn payouts_from_csv(input: &str) -> Result<Vec<Payout>, String> {
+    use std::str::FromStr;
+
+    let mut payouts = Vec::new();
+    for (line_number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (line_number == 0 && line.to_lowercase().starts_with("user")) {
+            continue;
+        }
+
+        let mut fields = line.split(',').map(str::trim);
+        let (user, amount) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(user), Some(amount), None) => (user, amount),
+            _ => return Err(format!("Line {}: expected `user,amount`", line_number + 1)),
+        };
+
+        payouts.push(Payout {
+            user: Pubkey::from_str(user)
+                .map_err(|e| format!("Line {}: invalid user: {}", line_number + 1, e))?,
+            amount: amount
+                .parse()
+                .map_err(|e| format!("Line {}: invalid amount: {}", line_number + 1, e))?,
+        });
+    }
+    Ok(payouts)
+}
+
+// Parse a JSON array of `{ "user": "<pubkey>", "amount": <u64> }` objects
+#[cfg(not(target_os = "solana"))]
+pub fn payouts_from_json(input: &str) -> Result<Vec<Payout>, String> {
+    use std::str::FromStr;
+
+    let entries: Vec<serde_json::Value> =
+        serde_json::from_str(input).map_err(|e| format!("Invalid payouts JSON: {}", e))?;
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let user = entry["user"]
+                .as_str()
+                .ok_or_else(|| format!("Entry {}: missing user", index))?;
+            let amount = entry["amount"]
+                .as_u64()
+                .ok_or_else(|| format!("Entry {}: missing amount", index))?;
+            Ok(Payout {
+                user: Pubkey::from_str(user)
+                    .map_err(|e| format!("Entry {}: invalid user: {}", index, e))?,
+                amount,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payouts(count: u64) -> Vec<Payout> {
+        (0..count)
+            .map(|index| Payout {
+                user: Pubkey::new_unique(),
+                amount: 1_000 + index,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_proof_verifies_against_the_root() {
+        // Odd sizes leave a node without a sibling on some layer
+        for count in [1, 2, 3, 5, 7, 8, 13] {
+            let tree = MerkleTree::new(payouts(count)).unwrap();
+            for (index, payout) in tree.payouts.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                let leaf = leaf_hash(index as u64, &payout.user, payout.amount);
+                assert!(
+                    verify_proof(&proof, &tree.root(), leaf),
+                    "leaf {} of {}",
+                    index,
+                    count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn single_leaf_is_its_own_root() {
+        let tree = MerkleTree::new(payouts(1)).unwrap();
+        let payout = &tree.payouts[0];
+
+        assert_eq!(tree.root(), leaf_hash(0, &payout.user, payout.amount));
+        assert!(tree.proof(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn tampered_leaves_do_not_verify() {
+        let tree = MerkleTree::new(payouts(5)).unwrap();
+        let payout = &tree.payouts[2];
+        let proof = tree.proof(2).unwrap();
+
+        assert!(!verify_proof(
+            &proof,
+            &tree.root(),
+            leaf_hash(2, &payout.user, payout.amount + 1)
+        ));
+        assert!(!verify_proof(
+            &proof,
+            &tree.root(),
+            leaf_hash(3, &payout.user, payout.amount)
+        ));
+        assert!(!verify_proof(
+            &proof,
+            &tree.root(),
+            leaf_hash(2, &Pubkey::new_unique(), payout.amount)
+        ));
+    }
+
+    #[test]
+    fn proof_index_out_of_range_is_none() {
+        let tree = MerkleTree::new(payouts(3)).unwrap();
+        assert!(tree.proof(3).is_none());
+    }
+
+    #[test]
+    fn empty_payouts_are_rejected() {
+        assert!(MerkleTree::new(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn totals_match_payouts() {
+        let tree = MerkleTree::new(payouts(4)).unwrap();
+        assert_eq!(tree.leaf_count(), 4);
+        assert_eq!(tree.total_amount(), 1_000 + 1_001 + 1_002 + 1_003);
+    }
+
+    #[test]
+    fn csv_skips_header_and_blank_lines() {
+        let expected = payouts(3);
+        let csv = format!(
+            "user,amount\n{},{}\n\n {} , {} \n{},{}\n",
+            expected[0].user,
+            expected[0].amount,
+            expected[1].user,
+            expected[1].amount,
+            expected[2].user,
+            expected[2].amount
+        );
+
+        assert_eq!(payouts_from_csv(&csv).unwrap(), expected);
+    }
+
+    #[test]
+    fn csv_rejects_malformed_lines() {
+        let user = Pubkey::new_unique();
+
+        assert!(payouts_from_csv(&format!("{}", user)).is_err());
+        assert!(payouts_from_csv(&format!("{},1,2", user)).is_err());
+        assert!(payouts_from_csv(&format!("{},-1", user)).is_err());
+        assert!(payouts_from_csv("not-a-key,1").is_err());
+    }
+
+    #[test]
+    fn json_parses_user_and_amount() {
+        let expected = payouts(2);
+        let json = format!(
+            r#"[{{"user": "{}", "amount": {}}}, {{"user": "{}", "amount": {}}}]"#,
+            expected[0].user, expected[0].amount, expected[1].user, expected[1].amount
+        );
+
+        assert_eq!(payouts_from_json(&json).unwrap(), expected);
+    }
+
+    #[test]
+    fn json_rejects_missing_or_invalid_fields() {
+        let user = Pubkey::new_unique();
+
+        assert!(payouts_from_json("{}").is_err());
+        assert!(payouts_from_json(&format!(r#"[{{"user": "{}"}}]"#, user)).is_err());
+        assert!(payouts_from_json(r#"[{"amount": 1}]"#).is_err());
+        assert!(payouts_from_json(r#"[{"user": "not-a-key", "amount": 1}]"#).is_err());
+        assert!(payouts_from_json(&format!(r#"[{{"user": "{}", "amount": -1}}]"#, user)).is_err());
+    }
+
+    #[test]
+    fn parsed_payouts_build_a_verifiable_tree() {
+        let expected = payouts(3);
+        let csv = expected
+            .iter()
+            .map(|payout| format!("{},{}", payout.user, payout.amount))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let tree = MerkleTree::new(payouts_from_csv(&csv).unwrap()).unwrap();
+        let proof = tree.proof(1).unwrap();
+        assert!(verify_proof(
+            &proof,
+            &tree.root(),
+            leaf_hash(1, &expected[1].user, expected[1].amount)
+        ));
+    }
+}
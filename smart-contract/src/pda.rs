@@ -0,0 +1,402 @@
+// Program derived addresses, one function per account type.
+//
+// Singleton accounts are seeded by a fixed name. Accounts that belong to a user, raid
+// program or competition are seeded by the first 32 bytes of sha256 over a formatted
+// string, which keeps variable-length keys and names within the 32 byte seed limit.
+// The handlers derive every address through this module, so clients that use it
+// always agree with the program. The `*_seed` functions return the seed that the
+// program signs with; the `*_address` functions return the address and canonical bump.
+// Cards store the canonical bump found when they were created, so later instructions
+// re-derive addresses with `create_address` instead of searching again.
+
+use sha2::{Digest, Sha256};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+pub const CONFIG_SEED: &[u8] = b"global_config";
+pub const PROGRAM_STATE_SEED: &[u8] = b"program_state";
+pub const TOKEN_MINT_SEED: &[u8] = b"pda_token_mint";
+pub const LEADERBOARD_SEED: &[u8] = b"program_leaderboard_state";
+pub const CLAIM_VAULT_SEED: &[u8] = b"reward_claim_vault";
+
+// Registries are split into pages seeded by [seed, page index as u64 little endian]
+pub const RAID_PROGRAMS_REGISTRY_SEED: &[u8] = b"raid_programs_page";
+pub const RAIDS_REGISTRY_SEED: &[u8] = b"raids_page";
+pub const COMPETITIONS_REGISTRY_SEED: &[u8] = b"competitions_page";
+
+fn hashed_seed(seed: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(seed.as_bytes());
+    hasher.finalize().into()
+}
+
+pub fn user_card_seed(user: &Pubkey) -> [u8; 32] {
+    hashed_seed(&format!("user_card_{}", user))
+}
+
+pub fn raid_program_seed(owner: &Pubkey, name: &str) -> [u8; 32] {
+    hashed_seed(&format!("raid_program_{}_{}", owner, name))
+}
+
+pub fn raid_program_token_account_seed(raid_program: &Pubkey) -> [u8; 32] {
+    hashed_seed(&format!("raid_program_token_account_{}", raid_program))
+}
+
+// PvP competitions are keyed by both programs, every other type only by its sequence
+pub fn competition_seed(
+    competition_type: &str,
+    sequence: u64,
+    pvp_programs: Option<(&Pubkey, &Pubkey)>,
+) -> [u8; 32] {
+    match pvp_programs {
+        Some((challenger, challenged)) => hashed_seed(&format!(
+            "{}_{}_{}_{}",
+            challenger, competition_type, sequence, challenged
+        )),
+        None => hashed_seed(&format!("{}_{}", competition_type, sequence)),
+    }
+}
+
+pub fn raid_card_seed(competition: &Pubkey, raid_program: &Pubkey) -> [u8; 32] {
+    hashed_seed(&format!("raid_{}_{}", competition, raid_program))
+}
+
+pub fn burn_ledger_seed(user: &Pubkey, raid_card: &Pubkey) -> [u8; 32] {
+    hashed_seed(&format!("burn_ledger_{}_{}", user, raid_card))
+}
+
+pub fn reward_vault_seed(competition: &Pubkey) -> [u8; 32] {
+    hashed_seed(&format!("reward_vault_{}", competition))
+}
+
+pub fn merkle_distributor_seed(competition: &Pubkey) -> [u8; 32] {
+    hashed_seed(&format!("merkle_distributor_{}", competition))
+}
+
+pub fn config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+pub fn program_state_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROGRAM_STATE_SEED], program_id)
+}
+
+pub fn token_mint_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TOKEN_MINT_SEED], program_id)
+}
+
+pub fn leaderboard_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LEADERBOARD_SEED], program_id)
+}
+
+pub fn claim_vault_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CLAIM_VAULT_SEED], program_id)
+}
+
+pub fn registry_page_address(program_id: &Pubkey, seed: &[u8], page_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seed, &page_index.to_le_bytes()], program_id)
+}
+
+pub fn user_card_address(program_id: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&user_card_seed(user)], program_id)
+}
+
+pub fn raid_program_address(program_id: &Pubkey, owner: &Pubkey, name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&raid_program_seed(owner, name)], program_id)
+}
+
+pub fn raid_program_token_account_address(
+    program_id: &Pubkey,
+    raid_program: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[&raid_program_token_account_seed(raid_program)],
+        program_id,
+    )
+}
+
+pub fn competition_address(
+    program_id: &Pubkey,
+    competition_type: &str,
+    sequence: u64,
+    pvp_programs: Option<(&Pubkey, &Pubkey)>,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[&competition_seed(competition_type, sequence, pvp_programs)],
+        program_id,
+    )
+}
+
+pub fn raid_card_address(
+    program_id: &Pubkey,
+    competition: &Pubkey,
+    raid_program: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&raid_card_seed(competition, raid_program)], program_id)
+}
+
+pub fn burn_ledger_address(program_id: &Pubkey, user: &Pubkey, raid_card: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&burn_ledger_seed(user, raid_card)], program_id)
+}
+
+pub fn reward_vault_address(program_id: &Pubkey, competition: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&reward_vault_seed(competition)], program_id)
+}
+
+pub fn merkle_distributor_address(program_id: &Pubkey, competition: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&merkle_distributor_seed(competition)], program_id)
+}
+
+// Rebuild an address from its seeds and a stored bump. This is a single hash, where
+// find_program_address may try many bumps before it finds the canonical one.
+pub fn create_address(
+    program_id: &Pubkey,
+    seeds: &[&[u8]],
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    let bump = [bump];
+    let mut seeds_with_bump = seeds.to_vec();
+    seeds_with_bump.push(&bump);
+    Ok(Pubkey::create_program_address(
+        &seeds_with_bump,
+        program_id,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sha256(seed: &str) -> [u8; 32] {
+        Sha256::digest(seed.as_bytes()).into()
+    }
+
+    // Check that `address` is the canonical PDA of `seeds` and that its bump rebuilds it
+    fn assert_derived(program_id: &Pubkey, (address, bump): (Pubkey, u8), seeds: &[&[u8]]) {
+        assert_eq!(
+            (address, bump),
+            Pubkey::find_program_address(seeds, program_id)
+        );
+        assert_eq!(create_address(program_id, seeds, bump), Ok(address));
+    }
+
+    #[test]
+    fn hashed_seeds_match_their_format_strings() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert_eq!(user_card_seed(&a), sha256(&format!("user_card_{}", a)));
+        assert_eq!(
+            raid_program_seed(&a, "Bulls"),
+            sha256(&format!("raid_program_{}_Bulls", a))
+        );
+        assert_eq!(
+            raid_program_token_account_seed(&a),
+            sha256(&format!("raid_program_token_account_{}", a))
+        );
+        assert_eq!(
+            competition_seed("4-program", 7, None),
+            sha256("4-program_7")
+        );
+        assert_eq!(
+            competition_seed("PvP", 7, Some((&a, &b))),
+            sha256(&format!("{}_PvP_7_{}", a, b))
+        );
+        assert_eq!(raid_card_seed(&a, &b), sha256(&format!("raid_{}_{}", a, b)));
+        assert_eq!(
+            burn_ledger_seed(&a, &b),
+            sha256(&format!("burn_ledger_{}_{}", a, b))
+        );
+        assert_eq!(
+            reward_vault_seed(&a),
+            sha256(&format!("reward_vault_{}", a))
+        );
+        assert_eq!(
+            merkle_distributor_seed(&a),
+            sha256(&format!("merkle_distributor_{}", a))
+        );
+    }
+
+    #[test]
+    fn addresses_are_derived_from_the_signing_seeds() {
+        let program_id = Pubkey::new_unique();
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert_derived(&program_id, config_address(&program_id), &[CONFIG_SEED]);
+        assert_derived(
+            &program_id,
+            program_state_address(&program_id),
+            &[PROGRAM_STATE_SEED],
+        );
+        assert_derived(
+            &program_id,
+            token_mint_address(&program_id),
+            &[TOKEN_MINT_SEED],
+        );
+        assert_derived(
+            &program_id,
+            leaderboard_address(&program_id),
+            &[LEADERBOARD_SEED],
+        );
+        assert_derived(
+            &program_id,
+            claim_vault_address(&program_id),
+            &[CLAIM_VAULT_SEED],
+        );
+        for seed in [
+            RAID_PROGRAMS_REGISTRY_SEED,
+            RAIDS_REGISTRY_SEED,
+            COMPETITIONS_REGISTRY_SEED,
+        ] {
+            assert_derived(
+                &program_id,
+                registry_page_address(&program_id, seed, 3),
+                &[seed, &3u64.to_le_bytes()],
+            );
+        }
+        assert_derived(
+            &program_id,
+            user_card_address(&program_id, &a),
+            &[&user_card_seed(&a)],
+        );
+        assert_derived(
+            &program_id,
+            raid_program_address(&program_id, &a, "Bulls"),
+            &[&raid_program_seed(&a, "Bulls")],
+        );
+        assert_derived(
+            &program_id,
+            raid_program_token_account_address(&program_id, &a),
+            &[&raid_program_token_account_seed(&a)],
+        );
+        assert_derived(
+            &program_id,
+            competition_address(&program_id, "PvP", 2, Some((&a, &b))),
+            &[&competition_seed("PvP", 2, Some((&a, &b)))],
+        );
+        assert_derived(
+            &program_id,
+            raid_card_address(&program_id, &a, &b),
+            &[&raid_card_seed(&a, &b)],
+        );
+        assert_derived(
+            &program_id,
+            burn_ledger_address(&program_id, &a, &b),
+            &[&burn_ledger_seed(&a, &b)],
+        );
+        assert_derived(
+            &program_id,
+            reward_vault_address(&program_id, &a),
+            &[&reward_vault_seed(&a)],
+        );
+        assert_derived(
+            &program_id,
+            merkle_distributor_address(&program_id, &a),
+            &[&merkle_distributor_seed(&a)],
+        );
+    }
+
+    #[cfg(feature = "no-entrypoint")]
+    #[test]
+    fn builders_use_the_module_addresses() {
+        use crate::instruction;
+
+        let program_id = Pubkey::new_unique();
+        let (user, competition, raid_card, raid_program) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let config = config_address(&program_id).0;
+        let mint = token_mint_address(&program_id).0;
+        let keys = |ix: solana_program::instruction::Instruction| -> Vec<Pubkey> {
+            ix.accounts.iter().map(|meta| meta.pubkey).collect()
+        };
+
+        let accounts = keys(instruction::initialize_program(&program_id, &user, None));
+        assert_eq!(accounts[1], mint);
+        assert_eq!(accounts[2], program_state_address(&program_id).0);
+        assert_eq!(accounts[3], leaderboard_address(&program_id).0);
+        assert_eq!(accounts[7], config);
+
+        let accounts = keys(instruction::create_user_card(
+            &program_id,
+            &user,
+            &user_card_address(&program_id, &user).0,
+        ));
+        assert_eq!(accounts[2], program_state_address(&program_id).0);
+        assert_eq!(accounts[4], config);
+
+        let accounts = keys(instruction::burn_tokens(
+            &program_id,
+            &user,
+            &user_card_address(&program_id, &user).0,
+            &Pubkey::new_unique(),
+            &raid_program,
+            &competition,
+            &raid_card,
+            1,
+        ));
+        assert_eq!(
+            accounts[2],
+            burn_ledger_address(&program_id, &user, &raid_card).0
+        );
+        assert_eq!(accounts[5], mint);
+        assert_eq!(accounts[10], config);
+
+        let accounts = keys(instruction::finalize_competition(
+            &program_id,
+            &user,
+            &competition,
+            &[(raid_card, raid_program)],
+        ));
+        assert_eq!(accounts[3], config);
+        assert_eq!(accounts[4], mint);
+        assert_eq!(
+            accounts[7],
+            reward_vault_address(&program_id, &competition).0
+        );
+        assert_eq!(
+            accounts[10],
+            raid_program_token_account_address(&program_id, &raid_program).0
+        );
+
+        let accounts = keys(instruction::credit_raid_rewards(
+            &program_id,
+            &user,
+            &raid_card,
+            &[user],
+            vec![1],
+        ));
+        assert_eq!(accounts[2], config);
+        assert_eq!(accounts[4], user_card_address(&program_id, &user).0);
+        assert_eq!(
+            accounts[5],
+            burn_ledger_address(&program_id, &user, &raid_card).0
+        );
+
+        let accounts = keys(instruction::claim_rewards(
+            &program_id,
+            &user,
+            &user_card_address(&program_id, &user).0,
+            &Pubkey::new_unique(),
+        ));
+        assert_eq!(accounts[2], claim_vault_address(&program_id).0);
+        assert_eq!(accounts[4], mint);
+        assert_eq!(accounts[6], config);
+
+        let accounts = keys(instruction::post_merkle_root(
+            &program_id,
+            &user,
+            &competition,
+            &merkle_distributor_address(&program_id, &competition).0,
+            [0; 32],
+            1,
+            1,
+        ));
+        assert_eq!(
+            accounts[3],
+            reward_vault_address(&program_id, &competition).0
+        );
+        assert_eq!(accounts[5], config);
+    }
+}
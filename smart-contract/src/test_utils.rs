@@ -0,0 +1,49 @@
+// Helpers shared by the unit tests
+
+use crate::Card;
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+// Owned backing for an AccountInfo, which only borrows its key, lamports and data
+pub struct TestAccount {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl TestAccount {
+    // A signed, writable account owned by `owner`
+    pub fn new(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> Self {
+        TestAccount {
+            key,
+            owner,
+            lamports: 1,
+            data,
+            is_signer: true,
+            is_writable: true,
+        }
+    }
+
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            self.is_signer,
+            self.is_writable,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            false,
+            0,
+        )
+    }
+}
+
+// The card in its current layout, header included
+pub fn packed<T: Card>(card: &T) -> Vec<u8> {
+    let mut data = vec![0; card.stored_len().unwrap()];
+    let bytes_written = card.pack_into(&mut data).unwrap();
+    data.truncate(bytes_written);
+    data
+}
@@ -0,0 +1,301 @@
+// Account checks shared by every instruction.
+//
+// Handlers validate each account as they take it from the account list, for example
+// `next_account_info(iter)?.card::<UserCard>(program_id)?.writable()?`, so no account
+// is read before its owner and discriminator have been confirmed. Every check hands
+// the account back, which lets them be chained in any order. Checks that need data
+// from another account, such as a PDA verified with a bump stored in a card, run
+// once that card has been read.
+
+use crate::{pda, BullPosterError, Card};
+use solana_program::{
+    account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey, system_program,
+    sysvar,
+};
+
+pub trait ValidateAccount: Sized {
+    // The account signed the transaction
+    fn signer(self) -> Result<Self, ProgramError>;
+
+    // The account was passed as writable
+    fn writable(self) -> Result<Self, ProgramError>;
+
+    // The account is owned by this program and holds a `T` in the current layout
+    fn card<T: Card>(self, program_id: &Pubkey) -> Result<Self, ProgramError>;
+
+    // The account is owned by this program, whatever it holds
+    fn program_owned(self, program_id: &Pubkey) -> Result<Self, ProgramError>;
+
+    // The account is at `expected`, usually a PDA, and fails with `error` otherwise
+    fn address(self, expected: &Pubkey, error: BullPosterError) -> Result<Self, ProgramError>;
+
+    // The account has not been created yet
+    fn uninitialized(self, error: BullPosterError) -> Result<Self, ProgramError>;
+
+    // The program's token mint, rebuilt from its stored bump and owned by Token-2022
+    fn token_mint(self, program_id: &Pubkey, bump: u8) -> Result<Self, ProgramError>;
+
+    fn token_program(self) -> Result<Self, ProgramError>;
+
+    fn system_program(self) -> Result<Self, ProgramError>;
+
+    fn rent_sysvar(self) -> Result<Self, ProgramError>;
+}
+
+impl ValidateAccount for &AccountInfo<'_> {
+    fn signer(self) -> Result<Self, ProgramError> {
+        if !self.is_signer {
+            msg!("Account {} must sign", self.key);
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(self)
+    }
+
+    fn writable(self) -> Result<Self, ProgramError> {
+        if !self.is_writable {
+            msg!("Account {} must be writable", self.key);
+            return Err(BullPosterError::AccountNotWritable.into());
+        }
+        Ok(self)
+    }
+
+    fn card<T: Card>(self, program_id: &Pubkey) -> Result<Self, ProgramError> {
+        self.program_owned(program_id)?;
+        T::check_header(&self.data.borrow())?;
+        Ok(self)
+    }
+
+    fn program_owned(self, program_id: &Pubkey) -> Result<Self, ProgramError> {
+        if self.owner != program_id {
+            msg!("Account {} is owned by {}", self.key, self.owner);
+            return Err(BullPosterError::InvalidAccountOwner.into());
+        }
+        Ok(self)
+    }
+
+    fn address(self, expected: &Pubkey, error: BullPosterError) -> Result<Self, ProgramError> {
+        if self.key != expected {
+            msg!("Expected account {}, got {}", expected, self.key);
+            return Err(error.into());
+        }
+        Ok(self)
+    }
+
+    fn uninitialized(self, error: BullPosterError) -> Result<Self, ProgramError> {
+        if !self.data_is_empty() {
+            return Err(error.into());
+        }
+        Ok(self)
+    }
+
+    fn token_mint(self, program_id: &Pubkey, bump: u8) -> Result<Self, ProgramError> {
+        let token_mint_pda = pda::create_address(program_id, &[pda::TOKEN_MINT_SEED], bump)?;
+        if self.key != &token_mint_pda || self.owner != &spl_token_2022::id() {
+            return Err(BullPosterError::InvalidTokenMint.into());
+        }
+        Ok(self)
+    }
+
+    fn token_program(self) -> Result<Self, ProgramError> {
+        if !spl_token_2022::check_id(self.key) {
+            msg!("Expected the Token-2022 program, got {}", self.key);
+            return Err(BullPosterError::InvalidTokenProgram.into());
+        }
+        Ok(self)
+    }
+
+    fn system_program(self) -> Result<Self, ProgramError> {
+        if !system_program::check_id(self.key) {
+            return Err(BullPosterError::InvalidSystemProgram.into());
+        }
+        Ok(self)
+    }
+
+    fn rent_sysvar(self) -> Result<Self, ProgramError> {
+        if !sysvar::rent::check_id(self.key) {
+            return Err(BullPosterError::InvalidRentSysvar.into());
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{packed, TestAccount};
+    use crate::{GlobalConfig, UserCard};
+
+    fn config_data() -> Vec<u8> {
+        packed(&GlobalConfig::default())
+    }
+
+    fn error(result: Result<&AccountInfo, ProgramError>) -> ProgramError {
+        result.map(|_| ()).unwrap_err()
+    }
+
+    #[test]
+    fn card_accepts_a_current_card() {
+        let program_id = Pubkey::new_unique();
+        let mut account = TestAccount::new(Pubkey::new_unique(), program_id, config_data());
+
+        assert!(account.info().card::<GlobalConfig>(&program_id).is_ok());
+    }
+
+    #[test]
+    fn card_rejects_the_wrong_owner() {
+        let program_id = Pubkey::new_unique();
+        let mut account =
+            TestAccount::new(Pubkey::new_unique(), Pubkey::new_unique(), config_data());
+
+        assert_eq!(
+            error(account.info().card::<GlobalConfig>(&program_id)),
+            BullPosterError::InvalidAccountOwner.into()
+        );
+    }
+
+    #[test]
+    fn card_rejects_the_wrong_discriminator() {
+        let program_id = Pubkey::new_unique();
+        let mut account = TestAccount::new(Pubkey::new_unique(), program_id, config_data());
+
+        assert_eq!(
+            error(account.info().card::<UserCard>(&program_id)),
+            BullPosterError::InvalidAccountDiscriminator.into()
+        );
+
+        let mut empty = TestAccount::new(Pubkey::new_unique(), program_id, Vec::new());
+        assert_eq!(
+            error(empty.info().card::<GlobalConfig>(&program_id)),
+            BullPosterError::InvalidAccountDiscriminator.into()
+        );
+    }
+
+    #[test]
+    fn card_rejects_an_old_version() {
+        let program_id = Pubkey::new_unique();
+        let mut data = config_data();
+        data[8] = GlobalConfig::VERSION - 1;
+        let mut account = TestAccount::new(Pubkey::new_unique(), program_id, data);
+
+        assert_eq!(
+            error(account.info().card::<GlobalConfig>(&program_id)),
+            BullPosterError::UnsupportedAccountVersion.into()
+        );
+    }
+
+    #[test]
+    fn address_rejects_the_wrong_pda() {
+        let program_id = Pubkey::new_unique();
+        let (config_pda, _) = pda::config_address(&program_id);
+        let mut config = TestAccount::new(config_pda, program_id, config_data());
+        let mut spoofed = TestAccount::new(Pubkey::new_unique(), program_id, config_data());
+
+        assert!(config
+            .info()
+            .address(&config_pda, BullPosterError::InvalidConfigAccount)
+            .is_ok());
+        assert_eq!(
+            error(
+                spoofed
+                    .info()
+                    .address(&config_pda, BullPosterError::InvalidConfigAccount)
+            ),
+            BullPosterError::InvalidConfigAccount.into()
+        );
+    }
+
+    #[test]
+    fn token_mint_rejects_the_wrong_pda_or_owner() {
+        let program_id = Pubkey::new_unique();
+        let (mint_pda, bump) = pda::token_mint_address(&program_id);
+        let mut mint = TestAccount::new(mint_pda, spl_token_2022::id(), Vec::new());
+        let mut spoofed = TestAccount::new(Pubkey::new_unique(), spl_token_2022::id(), Vec::new());
+        let mut wrong_owner = TestAccount::new(mint_pda, Pubkey::new_unique(), Vec::new());
+
+        assert!(mint.info().token_mint(&program_id, bump).is_ok());
+        assert_eq!(
+            error(spoofed.info().token_mint(&program_id, bump)),
+            BullPosterError::InvalidTokenMint.into()
+        );
+        assert_eq!(
+            error(wrong_owner.info().token_mint(&program_id, bump)),
+            BullPosterError::InvalidTokenMint.into()
+        );
+    }
+
+    #[test]
+    fn signer_rejects_a_missing_signature() {
+        let mut account = TestAccount::new(Pubkey::new_unique(), system_program::id(), Vec::new());
+        assert!(account.info().signer().is_ok());
+
+        account.is_signer = false;
+        assert_eq!(
+            error(account.info().signer()),
+            ProgramError::MissingRequiredSignature
+        );
+    }
+
+    #[test]
+    fn writable_rejects_a_read_only_account() {
+        let program_id = Pubkey::new_unique();
+        let mut account = TestAccount::new(Pubkey::new_unique(), program_id, config_data());
+        assert!(account.info().writable().is_ok());
+
+        account.is_writable = false;
+        assert_eq!(
+            error(
+                account
+                    .info()
+                    .card::<GlobalConfig>(&program_id)
+                    .and_then(ValidateAccount::writable)
+            ),
+            BullPosterError::AccountNotWritable.into()
+        );
+    }
+
+    #[test]
+    fn uninitialized_rejects_an_account_with_data() {
+        let program_id = Pubkey::new_unique();
+        let mut empty = TestAccount::new(Pubkey::new_unique(), system_program::id(), Vec::new());
+        let mut created = TestAccount::new(Pubkey::new_unique(), program_id, config_data());
+
+        assert!(empty
+            .info()
+            .uninitialized(BullPosterError::UserCardAlreadyExists)
+            .is_ok());
+        assert_eq!(
+            error(
+                created
+                    .info()
+                    .uninitialized(BullPosterError::UserCardAlreadyExists)
+            ),
+            BullPosterError::UserCardAlreadyExists.into()
+        );
+    }
+
+    #[test]
+    fn program_checks_reject_spoofed_programs() {
+        let mut spoofed = TestAccount::new(Pubkey::new_unique(), Pubkey::default(), Vec::new());
+
+        assert_eq!(
+            error(spoofed.info().token_program()),
+            BullPosterError::InvalidTokenProgram.into()
+        );
+        assert_eq!(
+            error(spoofed.info().system_program()),
+            BullPosterError::InvalidSystemProgram.into()
+        );
+        assert_eq!(
+            error(spoofed.info().rent_sysvar()),
+            BullPosterError::InvalidRentSysvar.into()
+        );
+
+        let mut token_program =
+            TestAccount::new(spl_token_2022::id(), Pubkey::default(), Vec::new());
+        let mut system = TestAccount::new(system_program::id(), Pubkey::default(), Vec::new());
+        let mut rent = TestAccount::new(sysvar::rent::id(), Pubkey::default(), Vec::new());
+        assert!(token_program.info().token_program().is_ok());
+        assert!(system.info().system_program().is_ok());
+        assert!(rent.info().rent_sysvar().is_ok());
+    }
+}